@@ -1,12 +1,13 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
-use cosmwasm_std::{Binary, Deps, DepsMut, Env, MessageInfo, Reply, Response, StdResult};
+use cosmwasm_std::{BankMsg, Binary, Deps, DepsMut, Env, MessageInfo, Reply, Response, StdResult};
 use cw2::set_contract_version;
 
-use crate::state::{Generator, GENERATORS, RandomState, RANDOM_STATE_HISTORY};
+use crate::state::{Config, CONFIG, DrandConfig, Generator, GENERATORS, RandomState, RANDOM_STATE_HISTORY, StakingConfig};
+use generator_staking::{bonded_stake, start_unbonding, validate_staking_config, validate_staking_update};
 use crate::error::ContractError;
 use crate::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, DrandCallBack};
-use crate::verify::{VerifyDrandSignature, derive_randomness_from_signature};
+use crate::drand_verify::{decode_public_key, decode_signature, verify_drand_signature, derive_randomness_from_signature};
 
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:bls-contract";
@@ -21,6 +22,15 @@ pub fn instantiate(
     _msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    validate_drand_config(&_msg.drand)?;
+    validate_staking_config(deps.api, &_msg.staking)?;
+
+    CONFIG.save(deps.storage, &Config{
+        owner: info.sender.clone(),
+        drand: _msg.drand,
+        staking: _msg.staking,
+    })?;
     
     let init_random_state = RandomState {
         round: 0,
@@ -42,7 +52,6 @@ pub fn instantiate(
 /// To make a contract migratable, you need
 /// - this entry_point implemented
 /// - only contract admin can migrate, so admin has to be set at contract initiation time
-/// Handling contract execution
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn migrate(_deps: DepsMut, _env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
     match msg {
@@ -67,36 +76,118 @@ pub fn execute(
         // With `Response` type, it is possible to dispatch message to invoke external logic.
         // See: https://github.com/CosmWasm/cosmwasm/blob/main/SEMANTICS.md#dispatching-messages
         ExecuteMsg::Register{moniker} => register(_deps,_info,moniker),
+        ExecuteMsg::DeleteGenerator{} => delete_generator(_deps,_env,_info),
+        ExecuteMsg::ClaimUnbonded{} => claim_unbonded(_deps,_env,_info),
         ExecuteMsg::Push{round,signature,previous_signature} => push(_deps,_info,_env,round,previous_signature,signature),
         ExecuteMsg::GetRandomValue{} => get_random_value(_deps,_info),
         ExecuteMsg::Recive{callback} => recive(_deps,_info,callback),
+        ExecuteMsg::UpdateDrandConfig{drand} => update_drand_config(_deps,_info,drand),
+        ExecuteMsg::UpdateStakingConfig{staking} => update_staking_config(_deps,_info,staking),
     }
 }
 
-fn recive(_deps: DepsMut, _info: MessageInfo, callback: DrandCallBack){
-    
+fn validate_drand_config(drand: &DrandConfig) -> Result<(), ContractError> {
+    decode_public_key(&drand.scheme, &drand.public_key)?;
+
+    let chain_hash = hex::decode(&drand.chain_hash)
+        .map_err(|_| ContractError::InvalidHex{field: "chain_hash".to_string()})?;
+    if chain_hash.len() != 32 {
+        return Err(ContractError::InvalidLength{field: "chain_hash".to_string(), expected: 32, actual: chain_hash.len()});
+    }
+
+    if drand.period == 0 {
+        return Err(ContractError::InvalidDrandPeriod{});
+    }
+
+    Ok(())
+}
+
+fn update_drand_config(_deps: DepsMut, _info: MessageInfo, drand: DrandConfig) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(_deps.storage)?;
+
+    if _info.sender != config.owner {
+        return Err(ContractError::Unauthorized{});
+    }
+
+    validate_drand_config(&drand)?;
+
+    config.drand = drand;
+    CONFIG.save(_deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "update drand config")
+        .add_attribute("chain_hash", config.drand.chain_hash))
+}
+
+fn recive(_deps: DepsMut, _info: MessageInfo, _callback: DrandCallBack) -> Result<Response, ContractError> {
+    Ok(Response::new().add_attribute("action", "recive".to_string()))
+}
+
+fn update_staking_config(_deps: DepsMut, _info: MessageInfo, staking: StakingConfig) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(_deps.storage)?;
+
+    if _info.sender != config.owner {
+        return Err(ContractError::Unauthorized{});
+    }
+
+    validate_staking_update(_deps.api, &config.staking, &staking)?;
+
+    config.staking = staking;
+    CONFIG.save(_deps.storage, &config)?;
+
+    Ok(Response::new().add_attribute("action", "update staking config"))
 }
 
 fn register(_deps: DepsMut, _info: MessageInfo, moniker: String) -> Result<Response, ContractError>{
     if GENERATORS.has(_deps.storage, _info.sender.clone()) {
-        return Err(ContractError::CustomError{val:"Address has been registered !".to_string()}); 
+        return Err(ContractError::AlreadyRegistered{});
     }
 
+    let staking = CONFIG.load(_deps.storage)?.staking;
+    let stake = bonded_stake(&staking, &_info.funds)?;
+
     GENERATORS.save(_deps.storage, _info.sender.clone(), &Generator{
         addr: _info.sender.clone(),
-        moniker: moniker,
+        moniker,
         reward: Vec::new(),
-    });
+        stake,
+    })?;
 
-    return Ok(Response::new()
+    Ok(Response::new()
         .add_attribute("action", "register".to_string())
-    );
+    )
+}
+
+fn delete_generator(_deps: DepsMut, _env: Env, _info: MessageInfo) -> Result<Response, ContractError> {
+    let generator = GENERATORS.may_load(_deps.storage, _info.sender.clone())?
+        .ok_or(ContractError::GeneratorNotRegistered{})?;
+
+    let staking = CONFIG.load(_deps.storage)?.staking;
+
+    GENERATORS.remove(_deps.storage, _info.sender.clone());
+    start_unbonding(_deps.storage, &_env, &staking, &_info.sender, generator.stake)?;
+
+    Ok(Response::new().add_attribute("action", "delete generator"))
+}
+
+fn claim_unbonded(_deps: DepsMut, _env: Env, _info: MessageInfo) -> Result<Response, ContractError> {
+    let staking = CONFIG.load(_deps.storage)?.staking;
+    let claimed = generator_staking::claim_unbonded(_deps.storage, &_env, &staking, &_info.sender)?;
+    let amount = claimed.amount;
+
+    Ok(Response::new()
+        .add_message(BankMsg::Send {
+            to_address: _info.sender.to_string(),
+            amount: vec![claimed],
+        })
+        .add_attribute("action", "claim unbonded")
+        .add_attribute("amount", amount))
 }
 
-fn push(_deps: DepsMut, _info: MessageInfo, _env: Env, round: u64, previous_signature: String, signature: String) -> Result<Response, ContractError> {
+fn push(_deps: DepsMut, _info: MessageInfo, _env: Env, round: u64, previous_signature: Option<String>, signature: String) -> Result<Response, ContractError> {
     let last_random_state_op = RANDOM_STATE_HISTORY.back(_deps.storage)?;
 
-    if !last_random_state_op.is_some(){
+    if last_random_state_op.is_none(){
         return Err(ContractError::CustomError{val:"State history error!".to_string()})
     }
 
@@ -108,46 +199,51 @@ fn push(_deps: DepsMut, _info: MessageInfo, _env: Env, round: u64, previous_sign
     }
 
     if GENERATORS.has(_deps.storage, _info.sender.clone()) {
-        let signature_bytes = hex::decode(signature.clone()).unwrap();
-        let previous_signature_bytes = hex::decode(previous_signature.clone()).unwrap();
-
-        let verify = VerifyDrandSignature(round, signature_bytes, previous_signature_bytes);
-        
-        if !verify {
-            return Err(ContractError::CustomError{val:"Verification failed!".to_string()});
-        }else{
-            let randomness = derive_randomness_from_signature(signature_bytes);
-
-            RANDOM_STATE_HISTORY.push_back(
-                        _deps.storage, &RandomState{
-                                        round: last_random_state.round + 1,
-                                        randomness: hex::encode(randomness),
-                                        origin_data: "".to_string(),
-                                        signature: previous_signature, 
-                                        generator: Some(_info.sender),
-                                        block_height: current_block_height + 1,
-                                    })?;
+        // each drand beacon is pushed at most once, in order
+        if round <= last_random_state.round {
+            return Err(ContractError::DrandRoundNotIncreasing{round, last_round: last_random_state.round});
         }
+
+        let config = CONFIG.load(_deps.storage)?;
+        let drand = config.drand;
+
+        let signature_bytes = decode_signature(&drand.scheme, "signature", &signature)?;
+        let previous_signature_bytes = previous_signature
+            .map(|previous_signature| decode_signature(&drand.scheme, "previous_signature", &previous_signature))
+            .transpose()?;
+
+        verify_drand_signature(&drand.scheme, &drand.public_key, round, &signature_bytes, previous_signature_bytes.as_deref())?;
+
+        let randomness = derive_randomness_from_signature(&signature_bytes);
+
+        RANDOM_STATE_HISTORY.push_back(
+                    _deps.storage, &RandomState{
+                                    round,
+                                    randomness: hex::encode(randomness),
+                                    origin_data: "".to_string(),
+                                    signature, 
+                                    generator: Some(_info.sender),
+                                    block_height: current_block_height + 1,
+                                })?;
     }else{
-        return Err(ContractError::CustomError{val:"Address has't been registerd!".to_string()});
+        return Err(ContractError::GeneratorNotRegistered{});
     }
 
-    return Ok(Response::new()
+    Ok(Response::new()
         .add_attribute("action", "push".to_string())
-    );
+    )
 }
 
 fn get_random_value(_deps: DepsMut, _info: MessageInfo) -> Result<Response, ContractError> {
     let last_random_state_op = RANDOM_STATE_HISTORY.back(_deps.storage)?;
-    if !last_random_state_op.is_some() {
+    if last_random_state_op.is_none() {
         return Err(ContractError::CustomError{val:"State history error!".to_string()}); 
     }
     
     let last_random_state = last_random_state_op.unwrap();
 
-    if last_random_state.generator.is_some() && 
-        GENERATORS.has(_deps.storage, last_random_state.generator.clone().unwrap()) {
-            let mut generator = GENERATORS.load(_deps.storage, last_random_state.generator.expect("error"))?;
+    if let Some(generator_addr) = last_random_state.generator.filter(|addr| GENERATORS.has(_deps.storage, addr.clone())) {
+            let mut generator = GENERATORS.load(_deps.storage, generator_addr)?;
                     
             for fund in _info.funds {
                 generator.reward.push(fund);
@@ -161,12 +257,12 @@ fn get_random_value(_deps: DepsMut, _info: MessageInfo) -> Result<Response, Cont
                 );
     }
 
-    return Ok(Response::new()
+    Ok(Response::new()
                 .add_attribute("round", "".to_string())
                 .add_attribute("randomness", "".to_string())
                 .add_attribute("origin_data", "".to_string())
                 .add_attribute("signature", "".to_string())
-            );
+            )
 }
 /// Handling contract query
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -195,13 +291,169 @@ pub fn reply(_deps: DepsMut, _env: Env, _msg: Reply) -> Result<Response, Contrac
 mod tests {
     use super::*;
     use cosmwasm_std::testing::{
-        mock_dependencies, mock_info,
+        mock_dependencies, mock_env, mock_info,
     };
+    use cosmwasm_std::{coins, Addr, Decimal, Uint128};
+    use crate::state::DrandScheme;
+    use cosmwasm_std::SubMsg;
+
+    const CREATOR: &str = "creator";
+    const GENERATOR: &str = "generator";
+    const TREASURY: &str = "treasury";
+    const UNBONDING_PERIOD: u64 = 3 * 24 * 60 * 60;
+
+    // curl -sS https://drand.cloudflare.com/public/72785
+    const ROUND_72785_PREVIOUS_SIGNATURE: &str = "a609e19a03c2fcc559e8dae14900aaefe517cb55c840f6e69bc8e4f66c8d18e8a609685d9917efbfb0c37f058c2de88f13d297c7e19e0ab24813079efe57a182554ff054c7638153f9b26a60e7111f71a0ff63d9571704905d3ca6df0b031747";
+    const ROUND_72785_SIGNATURE: &str = "82f5d3d2de4db19d40a6980e8aa37842a0e55d1df06bd68bddc8d60002e8e959eb9cfa368b3c1b77d18f02a54fe047b80f0989315f83b12a74fd8679c4f12aae86eaf6ab5690b34f1fddd50ee3cc6f6cdf59e95526d5a5d82aaa84fa6f181e42";
+
+    fn staking() -> StakingConfig {
+        StakingConfig {
+            denom: "ustake".to_string(),
+            min_stake: Uint128::new(1000),
+            unbonding_period: UNBONDING_PERIOD,
+            slash_fraction: Decimal::percent(10),
+            treasury: Addr::unchecked(TREASURY),
+        }
+    }
+
+    /// League of Entropy Mainnet (curl -sS https://drand.cloudflare.com/info)
+    fn leo_mainnet() -> DrandConfig {
+        DrandConfig {
+            public_key: "868f005eb8e6e4ca0a47c8a77ceaa5309a47978a7c71bc5cce96366b5d7a569937c529eeda66c7293784a9402801af31".to_string(),
+            chain_hash: "8990e7a9aaed2ffed73dbd7092123d6f289930540d7651336225dc172e51b2ce".to_string(),
+            genesis_time: 1595431050,
+            period: 30,
+            scheme: DrandScheme::PedersenBlsChained,
+        }
+    }
 
     #[test]
-    fn register_success() {  
+    fn register_success() {
+        let mut deps = mock_dependencies();
+        instantiate(deps.as_mut(), mock_env(), mock_info(CREATOR, &[]), InstantiateMsg{drand: leo_mainnet(), staking: staking()}).unwrap();
+
+        let register_msg = ExecuteMsg::Register{moniker: "generator".to_string()};
+        let res = execute(deps.as_mut(), mock_env(), mock_info("generator", &coins(1000, "ustake")), register_msg.clone()).unwrap();
+        assert_eq!(res, Response::new().add_attribute("action", "register".to_string()));
+
+        let generator = GENERATORS.load(&deps.storage, Addr::unchecked("generator")).unwrap();
+        assert_eq!(generator.moniker, "generator");
+
+        match execute(deps.as_mut(), mock_env(), mock_info("generator", &coins(1000, "ustake")), register_msg).unwrap_err() {
+            ContractError::AlreadyRegistered{} => {},
+            _ => panic!("")
+        }
+    }
+
+    #[test]
+    fn push_requires_increasing_rounds() {
+        let mut deps = mock_dependencies();
+        instantiate(deps.as_mut(), mock_env(), mock_info(CREATOR, &[]), InstantiateMsg{drand: leo_mainnet(), staking: staking()}).unwrap();
+        execute(deps.as_mut(), mock_env(), mock_info(GENERATOR, &coins(1000, "ustake")), ExecuteMsg::Register{moniker: "generator".to_string()}).unwrap();
+
+        let push_msg = ExecuteMsg::Push{
+            round: 72785,
+            previous_signature: Some(ROUND_72785_PREVIOUS_SIGNATURE.to_string()),
+            signature: ROUND_72785_SIGNATURE.to_string(),
+        };
+
+        let mut env = mock_env();
+        env.block.height += 32;
+        execute(deps.as_mut(), env.clone(), mock_info(GENERATOR, &[]), push_msg.clone()).unwrap();
+        assert_eq!(RANDOM_STATE_HISTORY.back(&deps.storage).unwrap().unwrap().round, 72785);
+
+        env.block.height += 32;
+        match execute(deps.as_mut(), env, mock_info(GENERATOR, &[]), push_msg).unwrap_err() {
+            ContractError::DrandRoundNotIncreasing{round, last_round} => {
+                assert_eq!(round, 72785);
+                assert_eq!(last_round, 72785);
+            },
+            _ => panic!("")
+        }
+    }
+
+    #[test]
+    fn register_requires_minimum_stake() {
+        let mut deps = mock_dependencies();
+        instantiate(deps.as_mut(), mock_env(), mock_info(CREATOR, &[]), InstantiateMsg{drand: leo_mainnet(), staking: staking()}).unwrap();
+
+        let register_msg = ExecuteMsg::Register{moniker: "generator".to_string()};
+        match execute(deps.as_mut(), mock_env(), mock_info(GENERATOR, &coins(999, "ustake")), register_msg.clone()).unwrap_err() {
+            ContractError::InsufficientStake{min_stake} => assert_eq!(min_stake, "1000ustake"),
+            _ => panic!("")
+        }
+
+        execute(deps.as_mut(), mock_env(), mock_info(GENERATOR, &coins(1000, "ustake")), register_msg).unwrap();
+        assert_eq!(GENERATORS.load(&deps.storage, Addr::unchecked(GENERATOR)).unwrap().stake, Uint128::new(1000));
+    }
+
+    #[test]
+    fn invalid_push_is_rejected_without_slashing() {
+        let mut deps = mock_dependencies();
+        instantiate(deps.as_mut(), mock_env(), mock_info(CREATOR, &[]), InstantiateMsg{drand: leo_mainnet(), staking: staking()}).unwrap();
+        execute(deps.as_mut(), mock_env(), mock_info(GENERATOR, &coins(1000, "ustake")), ExecuteMsg::Register{moniker: "generator".to_string()}).unwrap();
+
+        // a valid beacon claimed for another round
+        let push_msg = ExecuteMsg::Push{
+            round: 72786,
+            previous_signature: Some(ROUND_72785_PREVIOUS_SIGNATURE.to_string()),
+            signature: ROUND_72785_SIGNATURE.to_string(),
+        };
+
+        let mut env = mock_env();
+        env.block.height += 32;
+        match execute(deps.as_mut(), env, mock_info(GENERATOR, &[]), push_msg).unwrap_err() {
+            ContractError::DrandVerificationFailed{} => {},
+            _ => panic!("")
+        }
+
+        // drand beacons are public, so a bad one is an error rather than misbehaviour
+        assert_eq!(RANDOM_STATE_HISTORY.back(&deps.storage).unwrap().unwrap().round, 0);
+        assert_eq!(GENERATORS.load(&deps.storage, Addr::unchecked(GENERATOR)).unwrap().stake, Uint128::new(1000));
+    }
+
+    #[test]
+    fn deleted_generator_claims_stake_after_unbonding() {
+        let mut deps = mock_dependencies();
+        instantiate(deps.as_mut(), mock_env(), mock_info(CREATOR, &[]), InstantiateMsg{drand: leo_mainnet(), staking: staking()}).unwrap();
+        execute(deps.as_mut(), mock_env(), mock_info(GENERATOR, &coins(1000, "ustake")), ExecuteMsg::Register{moniker: "generator".to_string()}).unwrap();
+
+        execute(deps.as_mut(), mock_env(), mock_info(GENERATOR, &[]), ExecuteMsg::DeleteGenerator{}).unwrap();
+        match execute(deps.as_mut(), mock_env(), mock_info(GENERATOR, &[]), ExecuteMsg::ClaimUnbonded{}).unwrap_err() {
+            ContractError::StakeNotUnbonded{..} => {},
+            _ => panic!("")
+        }
+
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(UNBONDING_PERIOD);
+        let res = execute(deps.as_mut(), env.clone(), mock_info(GENERATOR, &[]), ExecuteMsg::ClaimUnbonded{}).unwrap();
+        assert_eq!(res.messages, vec![SubMsg::new(BankMsg::Send {
+            to_address: GENERATOR.to_string(),
+            amount: coins(1000, "ustake"),
+        })]);
+
+        match execute(deps.as_mut(), env, mock_info(GENERATOR, &[]), ExecuteMsg::ClaimUnbonded{}).unwrap_err() {
+            ContractError::NothingToClaim{} => {},
+            _ => panic!("")
+        }
+    }
+
+    #[test]
+    fn update_drand_config_only_by_owner() {
+        let mut deps = mock_dependencies();
+        instantiate(deps.as_mut(), mock_env(), mock_info(CREATOR, &[]), InstantiateMsg{drand: leo_mainnet(), staking: staking()}).unwrap();
+
+        let mut drand = leo_mainnet();
+        drand.period = 3;
+
+        let update_msg = ExecuteMsg::UpdateDrandConfig{drand: drand.clone()};
+        match execute(deps.as_mut(), mock_env(), mock_info("anyone", &[]), update_msg.clone()).unwrap_err() {
+            ContractError::Unauthorized{} => {},
+            _ => panic!("")
+        }
 
-        assert_eq!("","");
+        execute(deps.as_mut(), mock_env(), mock_info(CREATOR, &[]), update_msg).unwrap();
+        assert_eq!(CONFIG.load(&deps.storage).unwrap().drand, drand);
     }
 
 }
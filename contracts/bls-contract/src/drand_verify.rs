@@ -2,26 +2,167 @@ mod points;
 mod randomness;
 mod verify;
 
-use points::{
+pub use points::{
     g1_from_fixed, g1_from_fixed_unchecked, g1_from_variable, g1_from_variable_unchecked,
     g2_from_fixed, g2_from_fixed_unchecked, g2_from_variable, g2_from_variable_unchecked,
 };
-use randomness::derive_randomness;
-use verify::{verify, verify_step1, verify_step2, VerificationError};
+pub use randomness::derive_randomness;
+pub use verify::{verify, verify_g1_signature, verify_step1, verify_step2, verify_unchained, VerificationError};
+use paired::bls12_381::{G1Affine, G2Affine};
+use thiserror::Error;
 
+use crate::state::DrandScheme;
 
-/// Public key League of Entropy Mainnet (curl -sS https://drand.cloudflare.com/info)
-const PK_LEO_MAINNET: &str = "868f005eb8e6e4ca0a47c8a77ceaa5309a47978a7c71bc5cce96366b5d7a569937c529eeda66c7293784a9402801af31";
+/// Length of a compressed G1 point
+const G1_POINT_LENGTH: usize = 48;
+/// Length of a compressed G2 point
+const G2_POINT_LENGTH: usize = 96;
 
-pub fn VerifyDrandSignature(round: u64, signature: Vec<u8>, previous_signature: Vec<u8>) -> bool{
-    let pk = g1_from_fixed(PK_LEO_MAINNET).unwrap();
+#[derive(Error, Debug, PartialEq)]
+pub enum DrandError {
+    #[error("Invalid hex in {field}")]
+    InvalidHex { field: String },
 
-    match verify(&pk, round, &previous_signature, &signature) {
-        Err(err) => return false,
-        Ok(valid) => return true 
+    #[error("Invalid length of {field}: expected {expected} bytes, got {actual}")]
+    InvalidLength { field: String, expected: usize, actual: usize },
+
+    #[error("Invalid curve point for {field}: {msg}")]
+    InvalidPoint { field: String, msg: String },
+
+    #[error("Drand pairing check failed")]
+    VerificationFailed {},
+
+    #[error("Previous signature is required by chained drand schemes")]
+    MissingPreviousSignature {},
+}
+
+/// Public key of a drand network, on G1 or G2 depending on the scheme
+pub enum DrandPublicKey {
+    G1(G1Affine),
+    G2(G2Affine),
+}
+
+fn decode_fixed_hex(field: &str, value: &str, expected: usize) -> Result<Vec<u8>, DrandError> {
+    let bytes = hex::decode(value).map_err(|_| DrandError::InvalidHex{field: field.to_string()})?;
+
+    if bytes.len() != expected {
+        return Err(DrandError::InvalidLength{
+            field: field.to_string(),
+            expected,
+            actual: bytes.len(),
+        });
     }
+
+    Ok(bytes)
 }
 
-pub fn derive_randomness_from_signature(signature: Vec<u8>) -> Vec<u8>{
-    return derive_randomness(&signature);
-}
\ No newline at end of file
+fn invalid_public_key(err: impl ToString) -> DrandError {
+    DrandError::InvalidPoint{
+        field: "public_key".to_string(),
+        msg: err.to_string(),
+    }
+}
+
+/// Decodes a hex encoded drand signature of the given scheme and checks its length
+pub fn decode_signature(scheme: &DrandScheme, field: &str, signature: &str) -> Result<Vec<u8>, DrandError> {
+    let expected = if scheme.signs_on_g1() { G1_POINT_LENGTH } else { G2_POINT_LENGTH };
+    decode_fixed_hex(field, signature, expected)
+}
+
+/// Decodes a hex encoded drand network public key of the given scheme
+pub fn decode_public_key(scheme: &DrandScheme, public_key: &str) -> Result<DrandPublicKey, DrandError> {
+    if scheme.signs_on_g1() {
+        let bytes = decode_fixed_hex("public_key", public_key, G2_POINT_LENGTH)?;
+        g2_from_variable(&bytes).map(DrandPublicKey::G2).map_err(invalid_public_key)
+    } else {
+        let bytes = decode_fixed_hex("public_key", public_key, G1_POINT_LENGTH)?;
+        g1_from_variable(&bytes).map(DrandPublicKey::G1).map_err(invalid_public_key)
+    }
+}
+
+/// Verifies a drand beacon against the hex encoded network public key.
+/// `previous_signature` is only used, and required, by the chained scheme.
+pub fn verify_drand_signature(
+    scheme: &DrandScheme,
+    public_key: &str,
+    round: u64,
+    signature: &[u8],
+    previous_signature: Option<&[u8]>,
+) -> Result<(), DrandError> {
+    let result = match decode_public_key(scheme, public_key)? {
+        DrandPublicKey::G1(pk) if scheme.is_chained() => {
+            let previous_signature = previous_signature.ok_or(DrandError::MissingPreviousSignature{})?;
+            verify(&pk, round, previous_signature, signature)
+        }
+        DrandPublicKey::G1(pk) => verify_unchained(&pk, round, signature),
+        DrandPublicKey::G2(pk) => {
+            verify_g1_signature(&pk, round, signature, *scheme == DrandScheme::BlsUnchainedG1Rfc9380)
+        }
+    };
+
+    match result {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(DrandError::VerificationFailed{}),
+        Err(VerificationError::InvalidPoint{field, msg}) => Err(DrandError::InvalidPoint{field, msg}),
+    }
+}
+
+pub fn derive_randomness_from_signature(signature: &[u8]) -> Vec<u8>{
+    derive_randomness(signature).to_vec()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Public key League of Entropy Mainnet (curl -sS https://drand.cloudflare.com/info)
+    const PK_LEO_MAINNET: &str = "868f005eb8e6e4ca0a47c8a77ceaa5309a47978a7c71bc5cce96366b5d7a569937c529eeda66c7293784a9402801af31";
+
+    const CHAINED: DrandScheme = DrandScheme::PedersenBlsChained;
+
+    #[test]
+    fn verify_drand_signature_works() {
+        // curl -sS https://drand.cloudflare.com/public/72785
+        let signature = decode_signature(&CHAINED, "signature", "82f5d3d2de4db19d40a6980e8aa37842a0e55d1df06bd68bddc8d60002e8e959eb9cfa368b3c1b77d18f02a54fe047b80f0989315f83b12a74fd8679c4f12aae86eaf6ab5690b34f1fddd50ee3cc6f6cdf59e95526d5a5d82aaa84fa6f181e42").unwrap();
+        let previous_signature = decode_signature(&CHAINED, "previous_signature", "a609e19a03c2fcc559e8dae14900aaefe517cb55c840f6e69bc8e4f66c8d18e8a609685d9917efbfb0c37f058c2de88f13d297c7e19e0ab24813079efe57a182554ff054c7638153f9b26a60e7111f71a0ff63d9571704905d3ca6df0b031747").unwrap();
+
+        assert_eq!(verify_drand_signature(&CHAINED, PK_LEO_MAINNET, 72785, &signature, Some(&previous_signature)), Ok(()));
+        assert_eq!(verify_drand_signature(&CHAINED, PK_LEO_MAINNET, 321, &signature, Some(&previous_signature)), Err(DrandError::VerificationFailed{}));
+
+        assert_eq!(decode_signature(&CHAINED, "signature", "zz"), Err(DrandError::InvalidHex{field: "signature".to_string()}));
+        assert_eq!(decode_signature(&CHAINED, "signature", "aabb"),
+            Err(DrandError::InvalidLength{field: "signature".to_string(), expected: 96, actual: 2}));
+        assert_eq!(verify_drand_signature(&CHAINED, PK_LEO_MAINNET, 72785, &signature, None),
+            Err(DrandError::MissingPreviousSignature{}));
+    }
+
+    #[test]
+    fn verify_drand_signature_works_unchained() {
+        let scheme = DrandScheme::PedersenBlsUnchained;
+        // Public key and round 223344 of the unchained League of Entropy Testnet
+        let pk = "8200fc249deb0148eb918d6e213980c5d01acd7fc251900d9260136da3b54836ce125172399ddc69c4e3e11429b62c11";
+        let signature = decode_signature(&scheme, "signature", "94f6b85df7cce7237e8e7df66d794ddad092de5d8bb6a791b97e905aa89852e506ac36a792eba7021e22eebf34891f8914bf9a8dd9233ea0a4c5ca00ef8404999f899073dd2eade61fe54077fee8168f83dcb61a758b6883b38904054e64a433").unwrap();
+
+        assert_eq!(verify_drand_signature(&scheme, pk, 223344, &signature, None), Ok(()));
+        assert_eq!(verify_drand_signature(&scheme, pk, 223345, &signature, None), Err(DrandError::VerificationFailed{}));
+    }
+
+    #[test]
+    fn verify_drand_signature_works_on_g1() {
+        // https://api.drand.sh/dbd506d6ef76e5f386f41c651dcb808c5bcbd75471cc4eafa3f4df7ad4e4c493/info
+        let pk_fastnet = "a0b862a7527fee3a731bcb59280ab6abd62d5c0b6ea03dc4ddf6612fdfc9d01f01c31542541771903475eb1ec6615f8d0df0b8b6dce385811d6dcf8cbefb8759e5e616a3dfd054c928940766d9a5b9db91e3b697e5d70a975181e007f87fca5e";
+        let scheme = DrandScheme::BlsUnchainedOnG1;
+        let signature = decode_signature(&scheme, "signature", "98401ef9833e75bf06fda3243e4fcf6d075d62b45c2a59d26df5d5fcbdfd0c14ee89fc035abd5528a8c25b68fbecae65").unwrap();
+        assert_eq!(verify_drand_signature(&scheme, pk_fastnet, 23456, &signature, None), Ok(()));
+        assert_eq!(verify_drand_signature(&scheme, pk_fastnet, 23457, &signature, None), Err(DrandError::VerificationFailed{}));
+
+        // https://api.drand.sh/52db9ba70e0cc0f6eaf7803dd07447a1f5477735fd3f661792ba94600c84e971/info
+        let pk_quicknet = "83cf0f2896adee7eb8b5f01fcad3912212c437e0073e911fb90022d3e760183c8c4b450b6a0a6c3ac6a5776a2d1064510d1fec758c921cc22b0e17e63aaf4bcb5ed66304de9cf809bd274ca73bab4af5a6e9c76a4bc09e76eae8991ef5ece45a";
+        let scheme = DrandScheme::BlsUnchainedG1Rfc9380;
+        let signature = decode_signature(&scheme, "signature", "b75c69d0b72a5d906e854e808ba7e2accb1542ac355ae486d591aa9d43765482e26cd02df835d3546d23c4b13e0dfc92").unwrap();
+        assert_eq!(verify_drand_signature(&scheme, pk_quicknet, 123, &signature, None), Ok(()));
+        assert_eq!(verify_drand_signature(&DrandScheme::BlsUnchainedOnG1, pk_quicknet, 123, &signature, None),
+            Err(DrandError::VerificationFailed{}));
+    }
+}
@@ -1,6 +1,9 @@
 use cosmwasm_std::StdError;
+use generator_staking::StakingError;
 use thiserror::Error;
 
+use crate::drand_verify::DrandError;
+
 #[derive(Error, Debug)]
 pub enum ContractError {
     #[error("{0}")]
@@ -11,6 +14,73 @@ pub enum ContractError {
 
     #[error("Custom Error val: {val:?}")]
     CustomError { val: String },
+
+    #[error("Invalid hex in {field}")]
+    InvalidHex { field: String },
+
+    #[error("Invalid length of {field}: expected {expected} bytes, got {actual}")]
+    InvalidLength { field: String, expected: usize, actual: usize },
+
+    #[error("Invalid curve point for {field}: {msg}")]
+    InvalidPoint { field: String, msg: String },
+
+    #[error("Drand pairing check failed")]
+    DrandVerificationFailed {},
+
+    #[error("Previous signature is required by chained drand schemes")]
+    MissingPreviousSignature {},
+
+    #[error("Drand round {round} is not after the last pushed round {last_round}")]
+    DrandRoundNotIncreasing { round: u64, last_round: u64 },
+
+    #[error("Drand period must be greater than zero")]
+    InvalidDrandPeriod {},
+
+    #[error("A stake of at least {min_stake} is required")]
+    InsufficientStake { min_stake: String },
+
+    #[error("Stake is unbonding until {release_at}")]
+    StakeNotUnbonded { release_at: String },
+
+    #[error("Generator is not registered")]
+    GeneratorNotRegistered {},
+
+    #[error("Generator is already registered")]
+    AlreadyRegistered {},
+
+    #[error("Invalid staking config: {msg}")]
+    InvalidStakingConfig { msg: String },
+
+    #[error("Staking denom cannot be changed")]
+    StakingDenomChanged {},
+
+    #[error("Nothing to claim")]
+    NothingToClaim {},
     // Add any other custom errors you like here.
     // Look at https://docs.rs/thiserror/1.0.21/thiserror/ for details.
 }
+
+impl From<DrandError> for ContractError {
+    fn from(err: DrandError) -> Self {
+        match err {
+            DrandError::InvalidHex{field} => ContractError::InvalidHex{field},
+            DrandError::InvalidLength{field, expected, actual} => ContractError::InvalidLength{field, expected, actual},
+            DrandError::InvalidPoint{field, msg} => ContractError::InvalidPoint{field, msg},
+            DrandError::VerificationFailed{} => ContractError::DrandVerificationFailed{},
+            DrandError::MissingPreviousSignature{} => ContractError::MissingPreviousSignature{},
+        }
+    }
+}
+
+impl From<StakingError> for ContractError {
+    fn from(err: StakingError) -> Self {
+        match err {
+            StakingError::Std(err) => ContractError::Std(err),
+            StakingError::InvalidStakingConfig{msg} => ContractError::InvalidStakingConfig{msg},
+            StakingError::StakingDenomChanged{} => ContractError::StakingDenomChanged{},
+            StakingError::InsufficientStake{min_stake} => ContractError::InsufficientStake{min_stake},
+            StakingError::NothingToClaim{} => ContractError::NothingToClaim{},
+            StakingError::StakeNotUnbonded{release_at} => ContractError::StakeNotUnbonded{release_at},
+        }
+    }
+}
@@ -1,12 +1,12 @@
 use sha2::{Sha512, Digest};
 
 
-pub fn Sha512Hash(string: Vec<u8>) -> Vec<u8> {
+pub fn sha512_hash(string: Vec<u8>) -> Vec<u8> {
     let mut hasher = Sha512::new();
     // write input message
     hasher.update(&string);
             // read hash digest and consume hasher
     let result = hasher.finalize();
 
-    return result.to_vec();
+    result.to_vec()
 }
\ No newline at end of file
@@ -2,7 +2,7 @@ pub mod contract;
 mod error;
 pub mod msg;
 pub mod state;
-pub mod verify;
+pub mod drand_verify;
 pub mod hash;
 
 pub use crate::error::ContractError;
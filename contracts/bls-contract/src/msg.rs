@@ -1,19 +1,28 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use serde::{Deserialize, Serialize};
+
+use crate::state::{DrandConfig, StakingConfig};
 
 /// Message type for `instantiate` entry_point
 #[cw_serde]
 pub struct InstantiateMsg {
-    
+    pub drand: DrandConfig,
+    pub staking: StakingConfig,
 }
 
 /// Message type for `execute` entry_point
 #[cw_serde]
 pub enum ExecuteMsg {
+    /// Registers the sender as generator, the minimum stake must be sent along
     Register{moniker:String},
-    Push{round:u64,previous_signature:String,signature:String},
+    /// Deregisters the sender, its stake starts unbonding
+    DeleteGenerator{},
+    /// Withdraws the sender's stake once unbonded
+    ClaimUnbonded{},
+    Push{round:u64,previous_signature:Option<String>,signature:String},
     GetRandomValue{},
-    Recive{callback: DrandCallBack}
+    Recive{callback: DrandCallBack},
+    UpdateDrandConfig{drand: DrandConfig},
+    UpdateStakingConfig{staking: StakingConfig},
 }
 
 /// Message type for `migrate` entry_point
@@ -40,5 +49,6 @@ pub enum QueryMsg {
 #[cw_serde]
 pub struct DrandCallBack {
     randomness: String,
-    keyHash: String,
+    #[serde(rename = "keyHash")]
+    key_hash: String,
 }
@@ -1,14 +1,67 @@
 // use `cw_storage_plus` to create ORM-like interface to storage
 // see: https://crates.io/crates/cw-storage-plus
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Coin};
-use cw_storage_plus::{Deque,Map};
+use cosmwasm_std::{Addr, Coin, Uint128};
+use cw_storage_plus::{Deque,Item,Map};
+
+pub use generator_staking::{StakingConfig, Unbonding, UNBONDINGS};
+
+/// Signature scheme of a drand network, named after the network's `schemeID`
+#[cw_serde]
+pub enum DrandScheme {
+    /// Chained beacons signed on G2, each signature covers the previous one
+    #[serde(rename = "pedersen-bls-chained")]
+    PedersenBlsChained,
+    /// Unchained beacons signed on G2
+    #[serde(rename = "pedersen-bls-unchained")]
+    PedersenBlsUnchained,
+    /// Unchained beacons signed on G1 and hashed with the G2 domain (fastnet, testnet-g)
+    #[serde(rename = "bls-unchained-on-g1")]
+    BlsUnchainedOnG1,
+    /// Unchained beacons signed on G1 as specified in RFC 9380 (quicknet)
+    #[serde(rename = "bls-unchained-g1-rfc9380")]
+    BlsUnchainedG1Rfc9380,
+}
+
+impl DrandScheme {
+    /// Whether beacons of this scheme carry a previous signature
+    pub fn is_chained(&self) -> bool {
+        matches!(self, DrandScheme::PedersenBlsChained)
+    }
+
+    /// Whether the group public key is on G2 and signatures are on G1
+    pub fn signs_on_g1(&self) -> bool {
+        matches!(self, DrandScheme::BlsUnchainedOnG1 | DrandScheme::BlsUnchainedG1Rfc9380)
+    }
+}
+
+/// Chain info of the drand network beacons are verified against
+#[cw_serde]
+pub struct DrandConfig {
+    /// Hex encoded group public key
+    pub public_key: String,
+    /// Hex encoded chain hash
+    pub chain_hash: String,
+    /// Unix time in seconds of round 1
+    pub genesis_time: u64,
+    /// Seconds between two rounds
+    pub period: u64,
+    pub scheme: DrandScheme,
+}
+
+#[cw_serde]
+pub struct Config {
+    pub owner: Addr,
+    pub drand: DrandConfig,
+    pub staking: StakingConfig,
+}
 
 #[cw_serde]
 pub struct Generator {
     pub addr: Addr,
     pub moniker: String,
     pub reward: Vec<Coin>,
+    pub stake: Uint128,
 }
 
 #[cw_serde]
@@ -22,5 +75,6 @@ pub struct RandomState {
 }
 
 
+pub const CONFIG: Item<Config> = Item::new("config");
 pub const GENERATORS: Map<Addr,Generator> = Map::new("generators");
 pub const RANDOM_STATE_HISTORY: Deque<RandomState> = Deque::new("random_state_history");
\ No newline at end of file
@@ -1,6 +1,9 @@
 use cosmwasm_std::StdError;
+use generator_staking::StakingError;
 use thiserror::Error;
 
+use crate::verify::RsaError;
+
 #[derive(Error, Debug)]
 pub enum ContractError {
     #[error("{0}")]
@@ -11,6 +14,91 @@ pub enum ContractError {
 
     #[error("Custom Error val: {val:?}")]
     CustomError { val: String },
+
+    #[error("A stake of at least {min_stake} is required")]
+    InsufficientStake { min_stake: String },
+
+    #[error("Stake is unbonding until {release_at}")]
+    StakeNotUnbonded { release_at: String },
+
+    #[error("Generator is not registered")]
+    GeneratorNotRegistered {},
+
+    #[error("Generator is already registered")]
+    AlreadyRegistered {},
+
+    #[error("Invalid staking config: {msg}")]
+    InvalidStakingConfig { msg: String },
+
+    #[error("Staking denom cannot be changed")]
+    StakingDenomChanged {},
+
+    #[error("Invalid random.org config: {msg}")]
+    InvalidRandomOrgConfig { msg: String },
+
+    #[error("Nothing to claim")]
+    NothingToClaim {},
+
+    #[error("Invalid base64 in {field}")]
+    InvalidBase64 { field: String },
+
+    #[error("Invalid RSA public key: {msg}")]
+    InvalidPublicKey { msg: String },
+
+    #[error("RSA signature check failed")]
+    RsaVerificationFailed {},
+
+    #[error("Invalid random.org object: {msg}")]
+    InvalidRandomObject { msg: String },
+
+    #[error("Signed method {method} is not allowed")]
+    MethodNotAllowed { method: String },
+
+    #[error("Serial number {serial_number} is not after the last serial number {last_serial_number} pushed for its API key")]
+    SerialNumberNotIncreasing { serial_number: u64, last_serial_number: u64 },
+
+    #[error("Random object completed at {completion_time} is not fresh")]
+    RandomObjectNotFresh { completion_time: String },
+
+    #[error("Random object {field} does not match the config")]
+    RandomObjectMismatch { field: String },
+
+    #[error("Random object userData must be {expected}")]
+    UserDataMismatch { expected: String },
+
+    #[error("Trusted key {id} already exists")]
+    TrustedKeyExists { id: String },
+
+    #[error("Trusted key {id} not found")]
+    TrustedKeyNotFound { id: String },
+
+    #[error("Trusted key {id} was retired or expired when the object completed")]
+    TrustedKeyInactive { id: String },
+
+    #[error("Trusted key id must not be empty")]
+    EmptyTrustedKeyId {},
     // Add any other custom errors you like here.
     // Look at https://docs.rs/thiserror/1.0.21/thiserror/ for details.
 }
+
+impl From<StakingError> for ContractError {
+    fn from(err: StakingError) -> Self {
+        match err {
+            StakingError::Std(err) => ContractError::Std(err),
+            StakingError::InvalidStakingConfig{msg} => ContractError::InvalidStakingConfig{msg},
+            StakingError::StakingDenomChanged{} => ContractError::StakingDenomChanged{},
+            StakingError::InsufficientStake{min_stake} => ContractError::InsufficientStake{min_stake},
+            StakingError::NothingToClaim{} => ContractError::NothingToClaim{},
+            StakingError::StakeNotUnbonded{release_at} => ContractError::StakeNotUnbonded{release_at},
+        }
+    }
+}
+
+impl From<RsaError> for ContractError {
+    fn from(err: RsaError) -> Self {
+        match err {
+            RsaError::InvalidPublicKey{msg} => ContractError::InvalidPublicKey{msg},
+            RsaError::VerificationFailed{} => ContractError::RsaVerificationFailed{},
+        }
+    }
+}
@@ -8,5 +8,5 @@ pub fn sha512_hash(string: Vec<u8>) -> Vec<u8> {
             // read hash digest and consume hasher
     let result = hasher.finalize();
 
-    return result.to_vec();
+    result.to_vec()
 }
\ No newline at end of file
@@ -3,6 +3,7 @@ mod error;
 pub mod msg;
 pub mod state;
 pub mod hash;
+pub mod random_org;
 pub mod verify;
 
 pub use crate::error::ContractError;
@@ -1,18 +1,62 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use serde::{Deserialize, Serialize};
+use cosmwasm_std::{Binary, Timestamp};
+
+use crate::state::{RandomOrgConfig, StakingConfig};
+// only named in `#[returns]`, which is not compiled for wasm32
+#[cfg(not(target_arch = "wasm32"))]
+use cosmwasm_std::{Decimal, SignedDecimal};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::random_org::UserData;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::state::{RandomState, TrustedKey};
 
 /// Message type for `instantiate` entry_point
 #[cw_serde]
 pub struct InstantiateMsg {
-    
+    pub staking: StakingConfig,
+    pub random_org: RandomOrgConfig,
+    pub trusted_keys: Vec<NewTrustedKey>,
+}
+
+/// Encodings an RSA public key can be given in
+#[cw_serde]
+pub enum PublicKey {
+    /// Hex encoded modulus and public exponent
+    Components{n:String,e:String},
+    /// PEM encoded SubjectPublicKeyInfo
+    Pem{pem:String},
+    /// DER encoded SubjectPublicKeyInfo
+    Der{der:Binary},
+}
+
+#[cw_serde]
+pub struct NewTrustedKey {
+    pub id: String,
+    pub public_key: PublicKey,
+    pub expires_at: Option<Timestamp>,
 }
 
 /// Message type for `execute` entry_point
 #[cw_serde]
 pub enum ExecuteMsg {
+    /// Registers the sender as generator, the minimum stake must be sent along
     Register{moniker:String},
-    Push{random_obj:String,signature:String},
-    GetRandomValue{}
+    /// Deregisters the sender, its stake starts unbonding
+    DeleteGenerator{},
+    /// Withdraws the sender's stake once unbonded
+    ClaimUnbonded{},
+    /// `random_obj` is the base64 encoded JSON of a random.org signed `random` object
+    /// and `signature` its base64 encoded signature by the trusted key `key_id`. Its `userData` must be the
+    /// `NextUserData` query result.
+    Push{random_obj:String,signature:String,key_id:String},
+    GetRandomValue{},
+    UpdateStakingConfig{staking:StakingConfig},
+    UpdateRandomOrgConfig{random_org:RandomOrgConfig},
+    AddTrustedKey{trusted_key:NewTrustedKey},
+    /// Stops trusting a key right away
+    RetireTrustedKey{id:String},
+    /// Stops trusting objects the key signed that completed from `expires_at`, e.g. after its successor has been added
+    ExpireTrustedKey{id:String,expires_at:Timestamp},
 }
 
 /// Message type for `migrate` entry_point
@@ -23,42 +67,43 @@ pub enum MigrateMsg {}
 #[cw_serde]
 #[derive(QueryResponses)]
 pub enum QueryMsg {
-    // This example query variant indicates that any client can query the contract
-    // using `YourQuery` and it will return `YourQueryResponse`
-    // This `returns` information will be included in contract's schema
-    // which is used for client code generation.
-    //
-    // #[returns(YourQueryResponse)]
-    // YourQuery {},
+    /// Pushed random state of `round`, the latest one if `round` is not set
+    #[returns(RandomState)]
+    RandomState{round:Option<u64>},
+    /// All trusted keys, including retired and expired ones
+    #[returns(Vec<TrustedKey>)]
+    TrustedKeys{},
+    /// `userData` the signed object of the next round has to carry
+    #[returns(UserData)]
+    NextUserData{},
+    /// Values of a `generateSignedIntegers` object
+    #[returns(RandomValuesResponse<i64>)]
+    Integers{round:Option<u64>},
+    /// Values of a `generateSignedIntegerSequences` object
+    #[returns(RandomValuesResponse<Vec<i64>>)]
+    IntegerSequences{round:Option<u64>},
+    /// Values of a `generateSignedDecimalFractions` object
+    #[returns(RandomValuesResponse<Decimal>)]
+    DecimalFractions{round:Option<u64>},
+    /// Values of a `generateSignedGaussians` object
+    #[returns(RandomValuesResponse<SignedDecimal>)]
+    Gaussians{round:Option<u64>},
+    /// Values of a `generateSignedStrings` object
+    #[returns(RandomValuesResponse<String>)]
+    Strings{round:Option<u64>},
+    /// Values of a `generateSignedUUIDs` object
+    #[returns(RandomValuesResponse<String>)]
+    Uuids{round:Option<u64>},
+    /// Values of a `generateSignedBlobs` object
+    #[returns(RandomValuesResponse<Binary>)]
+    Blobs{round:Option<u64>},
 }
 
-// We define a custom struct for each query response
-// #[cw_serde]
-// pub struct YourQueryResponse {}
-
-/*
-#[derive(Debug, Deserialize, Serialize)]
-pub struct RandomObjectLicense {
-    type: String,
-    text: String,
-    infoUrl: String,
+/// Random values of a round, typed after the signed method that produced them
+#[cw_serde]
+pub struct RandomValuesResponse<T> {
+    pub round: u64,
+    pub serial_number: u64,
+    pub completion_time: Timestamp,
+    pub values: Vec<T>,
 }
-
-#[derive(Debug, Deserialize, Serialize)]
-pub struct RandomObject {
-    method: String,
-    hashedApiKey: String,
-    n: u32,
-    min: u32,
-    max: u32,
-    replacement: bool,
-    base: u32,
-    pregeneratedRandomization: String,
-    data: [u8:32],
-    license: RandomObjectLicense,
-    licenseData: String,
-    userData: String,
-    ticketData: String,
-    completionTime: String,
-    serialNumber: u32,
-}*/
\ No newline at end of file
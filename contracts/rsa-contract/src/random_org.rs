@@ -0,0 +1,411 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{from_json, Binary, Decimal, SignedDecimal};
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::error::ContractError;
+
+/// Signed methods of the random.org JSON-RPC API
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+pub enum SignedMethod {
+    #[serde(rename = "generateSignedIntegers")]
+    Integers,
+    #[serde(rename = "generateSignedIntegerSequences")]
+    IntegerSequences,
+    #[serde(rename = "generateSignedDecimalFractions")]
+    DecimalFractions,
+    #[serde(rename = "generateSignedGaussians")]
+    Gaussians,
+    #[serde(rename = "generateSignedStrings")]
+    Strings,
+    #[serde(rename = "generateSignedUUIDs")]
+    Uuids,
+    #[serde(rename = "generateSignedBlobs")]
+    Blobs,
+}
+
+impl SignedMethod {
+    /// Name of the method in the random.org API
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SignedMethod::Integers => "generateSignedIntegers",
+            SignedMethod::IntegerSequences => "generateSignedIntegerSequences",
+            SignedMethod::DecimalFractions => "generateSignedDecimalFractions",
+            SignedMethod::Gaussians => "generateSignedGaussians",
+            SignedMethod::Strings => "generateSignedStrings",
+            SignedMethod::Uuids => "generateSignedUUIDs",
+            SignedMethod::Blobs => "generateSignedBlobs",
+        }
+    }
+}
+
+/// License random.org issued the random values under
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RandomObjectLicense {
+    #[serde(rename = "type")]
+    pub license_type: String,
+    pub text: String,
+    pub info_url: Option<String>,
+}
+
+/// `userData` a signed object must carry, which binds it to one round of one deployment
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UserData {
+    pub contract: String,
+    pub chain_id: String,
+    pub round: u64,
+}
+
+/// Fields shared by the signed `random` objects of all methods.
+/// Fields the contract does not check are ignored, so random.org may add new ones.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RandomObject {
+    pub method: SignedMethod,
+    pub hashed_api_key: String,
+    pub n: u32,
+    pub license: RandomObjectLicense,
+    pub user_data: Option<UserData>,
+    /// UTC time formatted as `YYYY-MM-DD HH:MM:SSZ`
+    pub completion_time: String,
+    pub serial_number: u64,
+}
+
+/// Random values of a signed object, typed after its method
+#[cw_serde]
+pub enum RandomValues {
+    Integers(Vec<i64>),
+    IntegerSequences(Vec<Vec<i64>>),
+    DecimalFractions(Vec<Decimal>),
+    Gaussians(Vec<SignedDecimal>),
+    Strings(Vec<String>),
+    Uuids(Vec<String>),
+    Blobs(Vec<Binary>),
+}
+
+impl RandomValues {
+    pub fn method(&self) -> SignedMethod {
+        match self {
+            RandomValues::Integers(_) => SignedMethod::Integers,
+            RandomValues::IntegerSequences(_) => SignedMethod::IntegerSequences,
+            RandomValues::DecimalFractions(_) => SignedMethod::DecimalFractions,
+            RandomValues::Gaussians(_) => SignedMethod::Gaussians,
+            RandomValues::Strings(_) => SignedMethod::Strings,
+            RandomValues::Uuids(_) => SignedMethod::Uuids,
+            RandomValues::Blobs(_) => SignedMethod::Blobs,
+        }
+    }
+
+    /// Number of values, or of sequences and blobs
+    pub fn len(&self) -> usize {
+        match self {
+            RandomValues::Integers(values) => values.len(),
+            RandomValues::IntegerSequences(sequences) => sequences.len(),
+            RandomValues::DecimalFractions(values) => values.len(),
+            RandomValues::Gaussians(values) => values.len(),
+            RandomValues::Strings(values) | RandomValues::Uuids(values) => values.len(),
+            RandomValues::Blobs(blobs) => blobs.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// All integers of integer and integer sequence values, none for other methods
+    pub fn integers(&self) -> Vec<i64> {
+        match self {
+            RandomValues::Integers(values) => values.clone(),
+            RandomValues::IntegerSequences(sequences) => sequences.concat(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// Integer as random.org encodes it: a number in base 10, a string in other bases
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum EncodedInteger {
+    Number(i64),
+    Text(String),
+}
+
+impl EncodedInteger {
+    fn decode(self, base: u32) -> Result<i64, ContractError> {
+        match self {
+            EncodedInteger::Number(value) if base == 10 => Ok(value),
+            EncodedInteger::Text(text) if base != 10 => i64::from_str_radix(&text, base)
+                .map_err(|_| invalid_random_object("integer does not match its base")),
+            _ => Err(invalid_random_object("integer does not match its base")),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct IntegersData {
+    #[serde(default = "default_base")]
+    base: u32,
+    data: Vec<EncodedInteger>,
+}
+
+#[derive(Deserialize)]
+struct IntegerSequencesData {
+    #[serde(default = "default_base")]
+    base: u32,
+    data: Vec<Vec<EncodedInteger>>,
+}
+
+#[derive(Deserialize)]
+struct BlobsData {
+    #[serde(default = "default_blob_format")]
+    format: String,
+    data: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct Data<T> {
+    data: Vec<T>,
+}
+
+fn default_base() -> u32 {
+    10
+}
+
+fn default_blob_format() -> String {
+    "base64".to_string()
+}
+
+fn invalid_random_object(msg: &str) -> ContractError {
+    ContractError::InvalidRandomObject{msg: msg.to_string()}
+}
+
+fn parse<T: DeserializeOwned>(json: &[u8]) -> Result<T, ContractError> {
+    from_json(json).map_err(|err| ContractError::InvalidRandomObject{msg: err.to_string()})
+}
+
+/// Parses the JSON of a signed `random` object into its common fields and typed values
+pub fn parse_random_object(json: &[u8]) -> Result<(RandomObject, RandomValues), ContractError> {
+    // the JSON parser of CosmWasm has no floats, so fractions are read from strings
+    let json = quote_fractional_numbers(json)?;
+
+    let random_obj: RandomObject = parse(&json)?;
+
+    let values = match random_obj.method {
+        SignedMethod::Integers => {
+            let integers: IntegersData = parse(&json)?;
+            RandomValues::Integers(integers.data.into_iter()
+                .map(|value| value.decode(integers.base))
+                .collect::<Result<_, _>>()?)
+        },
+        SignedMethod::IntegerSequences => {
+            let sequences: IntegerSequencesData = parse(&json)?;
+            RandomValues::IntegerSequences(sequences.data.into_iter()
+                .map(|sequence| sequence.into_iter().map(|value| value.decode(sequences.base)).collect())
+                .collect::<Result<_, _>>()?)
+        },
+        SignedMethod::DecimalFractions => RandomValues::DecimalFractions(parse::<Data<Decimal>>(&json)?.data),
+        SignedMethod::Gaussians => RandomValues::Gaussians(parse::<Data<SignedDecimal>>(&json)?.data),
+        SignedMethod::Strings => RandomValues::Strings(parse::<Data<String>>(&json)?.data),
+        SignedMethod::Uuids => {
+            let uuids = parse::<Data<String>>(&json)?.data;
+            if !uuids.iter().all(|uuid| is_uuid(uuid)) {
+                return Err(invalid_random_object("malformed UUID"));
+            }
+            RandomValues::Uuids(uuids)
+        },
+        SignedMethod::Blobs => {
+            let blobs: BlobsData = parse(&json)?;
+            let decode = |blob: &String| match blobs.format.as_str() {
+                "base64" => base64::decode(blob).ok(),
+                "hex" => hex::decode(blob).ok(),
+                _ => None,
+            };
+            RandomValues::Blobs(blobs.data.iter()
+                .map(|blob| decode(blob).map(Binary::from).ok_or_else(|| invalid_random_object("malformed blob")))
+                .collect::<Result<_, _>>()?)
+        },
+    };
+
+    Ok((random_obj, values))
+}
+
+/// Whether `uuid` is formatted as xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx
+fn is_uuid(uuid: &str) -> bool {
+    uuid.len() == 36 && uuid.char_indices().all(|(i, c)| match i {
+        8 | 13 | 18 | 23 => c == '-',
+        _ => c.is_ascii_hexdigit(),
+    })
+}
+
+/// Wraps every JSON number with a fraction or exponent into a string, written without exponent.
+/// Strings and integers are copied unchanged.
+fn quote_fractional_numbers(json: &[u8]) -> Result<Vec<u8>, ContractError> {
+    let mut quoted = Vec::with_capacity(json.len());
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut i = 0;
+
+    while i < json.len() {
+        let byte = json[i];
+
+        if in_string {
+            quoted.push(byte);
+            if escaped {
+                escaped = false;
+            } else if byte == b'\\' {
+                escaped = true;
+            } else if byte == b'"' {
+                in_string = false;
+            }
+            i += 1;
+            continue;
+        }
+
+        if byte == b'-' || byte.is_ascii_digit() {
+            let start = i;
+            while i < json.len() && matches!(json[i], b'0'..=b'9' | b'-' | b'+' | b'.' | b'e' | b'E') {
+                i += 1;
+            }
+
+            let number = &json[start..i];
+            if number.iter().any(|byte| matches!(byte, b'.' | b'e' | b'E')) {
+                let number = core::str::from_utf8(number).map_err(|_| invalid_random_object("malformed number"))?;
+                quoted.push(b'"');
+                quoted.extend(without_exponent(number)?.as_bytes());
+                quoted.push(b'"');
+            } else {
+                quoted.extend(number);
+            }
+            continue;
+        }
+
+        in_string = byte == b'"';
+        quoted.push(byte);
+        i += 1;
+    }
+
+    Ok(quoted)
+}
+
+/// Writes a JSON number like `-1.25e-3` as plain decimal `-0.00125`
+fn without_exponent(number: &str) -> Result<String, ContractError> {
+    let invalid = || invalid_random_object("malformed number");
+
+    let (mantissa, exponent) = match number.find(['e', 'E']) {
+        Some(i) => (&number[..i], number[i + 1..].parse::<i32>().map_err(|_| invalid())?),
+        None => (number, 0),
+    };
+    let (sign, mantissa) = match mantissa.strip_prefix('-') {
+        Some(mantissa) => ("-", mantissa),
+        None => ("", mantissa),
+    };
+    let (whole, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+
+    if whole.is_empty() || !whole.bytes().chain(fraction.bytes()).all(|byte| byte.is_ascii_digit()) || exponent.abs() > 64 {
+        return Err(invalid());
+    }
+
+    let digits = format!("{}{}", whole, fraction);
+    let point = whole.len() as i32 + exponent;
+
+    let plain = if point <= 0 {
+        format!("0.{}{}", "0".repeat(-point as usize), digits)
+    } else if point as usize >= digits.len() {
+        format!("{}{}", digits, "0".repeat(point as usize - digits.len()))
+    } else {
+        format!("{}.{}", &digits[..point as usize], &digits[point as usize..])
+    };
+
+    Ok(format!("{}{}", sign, plain))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn signed_object(method: &str, n: u32, params: &str) -> Vec<u8> {
+        format!(r#"{{"method":"{}","hashedApiKey":"key","n":{},{},"license":{{"type":"developer","text":"test","infoUrl":null}},"licenseData":null,"userData":null,"ticketData":null,"completionTime":"2022-12-08 02:52:15Z","serialNumber":3}}"#,
+            method, n, params).into_bytes()
+    }
+
+    #[test]
+    fn parse_integers() {
+        let (random_obj, values) = parse_random_object(&signed_object("generateSignedIntegers", 3,
+            r#""min":-5,"max":5,"replacement":true,"base":10,"data":[-5,0,5]"#)).unwrap();
+        assert_eq!(random_obj.method, SignedMethod::Integers);
+        assert_eq!(random_obj.serial_number, 3);
+        assert_eq!(values, RandomValues::Integers(vec![-5, 0, 5]));
+
+        let (_, values) = parse_random_object(&signed_object("generateSignedIntegers", 2,
+            r#""min":0,"max":255,"replacement":true,"base":16,"data":["ff","0a"]"#)).unwrap();
+        assert_eq!(values, RandomValues::Integers(vec![255, 10]));
+
+        // numbers must match the base
+        parse_random_object(&signed_object("generateSignedIntegers", 1,
+            r#""min":0,"max":255,"replacement":true,"base":16,"data":[10]"#)).unwrap_err();
+    }
+
+    #[test]
+    fn parse_integer_sequences() {
+        let (_, values) = parse_random_object(&signed_object("generateSignedIntegerSequences", 2,
+            r#""length":[3,2],"min":1,"max":[6,10],"replacement":false,"base":10,"data":[[3,1,6],[10,7]]"#)).unwrap();
+        assert_eq!(values, RandomValues::IntegerSequences(vec![vec![3, 1, 6], vec![10, 7]]));
+        assert_eq!(values.integers(), vec![3, 1, 6, 10, 7]);
+    }
+
+    #[test]
+    fn parse_decimal_fractions_and_gaussians() {
+        let (_, values) = parse_random_object(&signed_object("generateSignedDecimalFractions", 2,
+            r#""decimalPlaces":4,"replacement":true,"data":[0.1234,0.0007]"#)).unwrap();
+        assert_eq!(values, RandomValues::DecimalFractions(vec![
+            Decimal::from_str("0.1234").unwrap(),
+            Decimal::from_str("0.0007").unwrap(),
+        ]));
+
+        let (_, values) = parse_random_object(&signed_object("generateSignedGaussians", 3,
+            r#""mean":0.0,"standardDeviation":1.5,"significantDigits":3,"data":[-1.25,0.0342,1.5e-3]"#)).unwrap();
+        assert_eq!(values, RandomValues::Gaussians(vec![
+            SignedDecimal::from_str("-1.25").unwrap(),
+            SignedDecimal::from_str("0.0342").unwrap(),
+            SignedDecimal::from_str("0.0015").unwrap(),
+        ]));
+    }
+
+    #[test]
+    fn parse_strings_uuids_and_blobs() {
+        let (_, values) = parse_random_object(&signed_object("generateSignedStrings", 2,
+            r#""length":4,"characters":"ab.1","replacement":true,"data":["ab.1","1.1a"]"#)).unwrap();
+        assert_eq!(values, RandomValues::Strings(vec!["ab.1".to_string(), "1.1a".to_string()]));
+
+        let (_, values) = parse_random_object(&signed_object("generateSignedUUIDs", 1,
+            r#""data":["47849fd4-b790-4ba7-bbb0-2efd5cf7e5c5"]"#)).unwrap();
+        assert_eq!(values, RandomValues::Uuids(vec!["47849fd4-b790-4ba7-bbb0-2efd5cf7e5c5".to_string()]));
+        parse_random_object(&signed_object("generateSignedUUIDs", 1, r#""data":["not a uuid"]"#)).unwrap_err();
+
+        let (_, values) = parse_random_object(&signed_object("generateSignedBlobs", 2,
+            r#""size":16,"format":"hex","data":["aabb","0102"]"#)).unwrap();
+        assert_eq!(values, RandomValues::Blobs(vec![Binary::from(vec![0xaa, 0xbb]), Binary::from(vec![1, 2])]));
+
+        let (_, values) = parse_random_object(&signed_object("generateSignedBlobs", 1, r#""size":16,"data":["qrs="]"#)).unwrap();
+        assert_eq!(values, RandomValues::Blobs(vec![Binary::from(vec![0xaa, 0xbb])]));
+    }
+
+    #[test]
+    fn parse_rejects_unsigned_methods() {
+        parse_random_object(&signed_object("generateIntegers", 1, r#""data":[1]"#)).unwrap_err();
+    }
+
+    #[test]
+    fn without_exponent_works() {
+        assert_eq!(without_exponent("0.5").unwrap(), "0.5");
+        assert_eq!(without_exponent("-1.25e-3").unwrap(), "-0.00125");
+        assert_eq!(without_exponent("12E2").unwrap(), "1200");
+        assert_eq!(without_exponent("1.234e2").unwrap(), "123.4");
+        without_exponent("1e").unwrap_err();
+        without_exponent(".5").unwrap_err();
+    }
+}
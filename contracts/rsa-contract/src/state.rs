@@ -1,26 +1,87 @@
 // use `cw_storage_plus` to create ORM-like interface to storage
 // see: https://crates.io/crates/cw-storage-plus
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Coin};
-use cw_storage_plus::{Deque,Map};
+use cosmwasm_std::{Addr, Coin, Timestamp, Uint128};
+use cw_storage_plus::{Deque,Item,Map};
+
+use crate::random_org::{RandomValues, SignedMethod};
+
+pub use generator_staking::{StakingConfig, Unbonding, UNBONDINGS};
+
+/// Signed random.org objects accepted by `Push`
+#[cw_serde]
+pub struct RandomOrgConfig {
+    /// Signed methods objects may come from
+    pub allowed_methods: Vec<SignedMethod>,
+    /// Seconds an object stays fresh after its completion time
+    pub max_age: u64,
+    /// Number of random values, sequences or blobs every object must carry
+    pub n: u32,
+    /// Smallest integer accepted from the integer methods
+    pub min: i64,
+    /// Largest integer accepted from the integer methods
+    pub max: i64,
+}
+
+#[cw_serde]
+pub struct Config {
+    pub owner: Addr,
+    pub staking: StakingConfig,
+    pub random_org: RandomOrgConfig,
+}
+
+/// RSA public key signed objects are verified with
+#[cw_serde]
+pub struct TrustedKey {
+    pub id: String,
+    /// Hex encoded modulus
+    pub n: String,
+    /// Hex encoded public exponent
+    pub e: String,
+    /// Completion time from which objects signed by the key are no longer accepted
+    pub expires_at: Option<Timestamp>,
+    /// Retired keys no longer verify objects, they are kept to resolve `RandomState::verified_by`
+    pub retired: bool,
+}
+
+impl TrustedKey {
+    /// Whether objects completed at `time` are accepted with this key
+    // `Option::is_none_or` would need Rust 1.82
+    #[allow(clippy::unnecessary_map_or)]
+    pub fn is_active(&self, time: Timestamp) -> bool {
+        !self.retired && self.expires_at.map_or(true, |expires_at| time < expires_at)
+    }
+}
 
 #[cw_serde]
 pub struct Generator {
     pub addr: Addr,
     pub moniker: String,
     pub reward: Vec<Coin>,
+    pub stake: Uint128,
 }
 
 #[cw_serde]
 pub struct RandomState {
     pub round: u64,
+    /// Hex encoded sha512 of the signed object
     pub randomness: String,
+    /// Random values of the signed object
+    pub data: RandomValues,
+    pub serial_number: u64,
+    pub completion_time: Timestamp,
     pub origin_data: String,
     pub signature: String, 
+    /// Id of the trusted key that verified the signature
+    pub verified_by: Option<String>,
     pub generator: Option<Addr>,
     pub block_height: u64,
 }
 
 
+pub const CONFIG: Item<Config> = Item::new("config");
 pub const GENERATORS: Map<Addr,Generator> = Map::new("generators");
-pub const RANDOM_STATE_HISTORY: Deque<RandomState> = Deque::new("random_state_history");
\ No newline at end of file
+pub const TRUSTED_KEYS: Map<&str,TrustedKey> = Map::new("trusted_keys");
+pub const RANDOM_STATE_HISTORY: Deque<RandomState> = Deque::new("random_state_history");
+/// Serial number of the last object pushed for each random.org API key, keyed by `hashedApiKey`
+pub const LAST_SERIAL_NUMBERS: Map<&str,u64> = Map::new("last_serial_numbers");
@@ -1,24 +1,118 @@
-use openssl::rsa::{Rsa, Padding};
-use openssl::sign::Verifier;
-use openssl::bn::BigNum;
-use openssl::pkey::PKey;
-use openssl::hash::MessageDigest;
-
-const RANDOM_ORG_PUBLIC_KEY_N: &str = "ecedc74162e74f30828ffab0a08e2f8ff4fddb7ef07bbe2bc1c256db0e12bb320a565027e7285a25c69e429769987c2642ddda53c1b56daee7df197b85d78f921f9a12460cde254e84965d9022a3cf0db1ee55124089d992c827b3c47888692524f2275fa7e606312bb7562b8c8f01e47ab3de4a226e4a8866056e67541f26881b9acad3eb88a68220dd786dd70dc398e320f34bbdf86cda9150d6216b76839f0bf1aee6f23217d6b41976cba9d72836de30a27d356bbbdb757b2fe04615e12f60c3eaf22791549ef271abca7925c4a22f46be0cc28eecb618124e5ece353b97f4ed59ea1b1722eaeab26e5120af44a83444d816726c49592bcb24cfb4eee58798dd160e1098705411fcdf71640c9318f82db0ef447327e5422ba1f900ee0fbded67ff2109d9ce195987e0e021bde38d70f9d06a89b1dedc774a23259bb319fe812d267c836299389dcab41d6efe76781d541474fe99368a77984c7b3226abef04838d1cc68386b27f11daf293ad13aa3ca5ed1dee556edd74c70bd90be6a6775ea95de92c7db49d99436a038d33e53c885818c2dd78485799852b8670c2869389ad6bec6ff7a1e0cdfcb1651c70141397db01bd6464adb4826b3971640f98e4a38f109dcd211f068ca14dc1b77c064f589372e76e8712a7713cd81543d608b8cd177d32d0610a519cfffc62f12e56ac5868f25fac67e742abf8ae5582d39065";
-const RANDOM_ORG_PUBLIC_KEY_E: &str = "010001";
-
-pub fn VerifyRandomOrgSig(data: Vec<u8>, signature: Vec<u8>) -> bool{
-    let n: BigNum = BigNum::from_hex_str(RANDOM_ORG_PUBLIC_KEY_N).unwrap();    
-    let e: BigNum = BigNum::from_hex_str(RANDOM_ORG_PUBLIC_KEY_E).unwrap();
-
-    // Create Rsa public key from n,e components
-    let rsa = Rsa::from_public_components(n,e).expect("error");
-    let keypair = PKey::from_rsa(rsa).unwrap();
-
-    let mut verifier = Verifier::new(MessageDigest::sha512(), &keypair).unwrap();
-    verifier.update(&data).unwrap();
-    let verify = verifier.verify(&signature).unwrap();
-    drop(verifier);
-
-    return verify;
-}
\ No newline at end of file
+use rsa::pkcs8::DecodePublicKey;
+use rsa::traits::PublicKeyParts;
+use rsa::{BigUint, Pkcs1v15Sign, RsaPublicKey};
+use sha2::{Digest, Sha512};
+use thiserror::Error;
+
+// random.org signing key of the test vectors
+#[cfg(test)]
+pub(crate) const RANDOM_ORG_PUBLIC_KEY_N: &str = "ecedc74162e74f30828ffab0a08e2f8ff4fddb7ef07bbe2bc1c256db0e12bb320a565027e7285a25c69e429769987c2642ddda53c1b56daee7df197b85d78f921f9a12460cde254e84965d9022a3cf0db1ee55124089d992c827b3c47888692524f2275fa7e606312bb7562b8c8f01e47ab3de4a226e4a8866056e67541f26881b9acad3eb88a68220dd786dd70dc398e320f34bbdf86cda9150d6216b76839f0bf1aee6f23217d6b41976cba9d72836de30a27d356bbbdb757b2fe04615e12f60c3eaf22791549ef271abca7925c4a22f46be0cc28eecb618124e5ece353b97f4ed59ea1b1722eaeab26e5120af44a83444d816726c49592bcb24cfb4eee58798dd160e1098705411fcdf71640c9318f82db0ef447327e5422ba1f900ee0fbded67ff2109d9ce195987e0e021bde38d70f9d06a89b1dedc774a23259bb319fe812d267c836299389dcab41d6efe76781d541474fe99368a77984c7b3226abef04838d1cc68386b27f11daf293ad13aa3ca5ed1dee556edd74c70bd90be6a6775ea95de92c7db49d99436a038d33e53c885818c2dd78485799852b8670c2869389ad6bec6ff7a1e0cdfcb1651c70141397db01bd6464adb4826b3971640f98e4a38f109dcd211f068ca14dc1b77c064f589372e76e8712a7713cd81543d608b8cd177d32d0610a519cfffc62f12e56ac5868f25fac67e742abf8ae5582d39065";
+#[cfg(test)]
+pub(crate) const RANDOM_ORG_PUBLIC_KEY_E: &str = "010001";
+
+#[derive(Error, Debug, PartialEq)]
+pub enum RsaError {
+    #[error("Invalid RSA public key: {msg}")]
+    InvalidPublicKey { msg: String },
+
+    #[error("RSA signature check failed")]
+    VerificationFailed {},
+}
+
+/// Builds an RSA public key from its hex encoded modulus and exponent
+pub fn public_key_from_components(n: &str, e: &str) -> Result<RsaPublicKey, RsaError> {
+    let component = |name: &str, value: &str| {
+        hex::decode(value)
+            .map(|bytes| BigUint::from_bytes_be(&bytes))
+            .map_err(|_| RsaError::InvalidPublicKey{msg: format!("invalid hex in {}", name)})
+    };
+
+    RsaPublicKey::new(component("modulus", n)?, component("exponent", e)?)
+        .map_err(|err| RsaError::InvalidPublicKey{msg: err.to_string()})
+}
+
+/// Builds an RSA public key from a DER encoded SubjectPublicKeyInfo
+pub fn public_key_from_der(der: &[u8]) -> Result<RsaPublicKey, RsaError> {
+    RsaPublicKey::from_public_key_der(der)
+        .map_err(|err| RsaError::InvalidPublicKey{msg: err.to_string()})
+}
+
+/// Builds an RSA public key from a PEM encoded SubjectPublicKeyInfo (`-----BEGIN PUBLIC KEY-----`)
+pub fn public_key_from_pem(pem: &str) -> Result<RsaPublicKey, RsaError> {
+    RsaPublicKey::from_public_key_pem(pem)
+        .map_err(|err| RsaError::InvalidPublicKey{msg: err.to_string()})
+}
+
+/// Hex encoded modulus and exponent of an RSA public key
+pub fn public_key_components(public_key: &RsaPublicKey) -> (String, String) {
+    (hex::encode(public_key.n().to_bytes_be()), hex::encode(public_key.e().to_bytes_be()))
+}
+
+/// Verifies an RSASSA-PKCS1-v1_5 signature with SHA-512 over `data`
+pub fn verify_pkcs1v15_sha512(public_key: &RsaPublicKey, data: &[u8], signature: &[u8]) -> Result<(), RsaError> {
+    let hashed = Sha512::digest(data);
+
+    public_key
+        .verify(Pkcs1v15Sign::new::<Sha512>(), &hashed, signature)
+        .map_err(|_| RsaError::VerificationFailed{})
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // random.org signed integers, the same object the contract tests push
+    const RANDOM_OBJ: &str = "eyJtZXRob2QiOiJnZW5lcmF0ZVNpZ25lZEludGVnZXJzIiwiaGFzaGVkQXBpS2V5IjoiSUVicnY4NzFLZnBsdjNmdkFaWG9rRFg2S1o1N0pES2wyajhLNktRZkxnRk12MDF6ZktsWnJweXFnQ3MyRE9rRVg4LzcvQ2xIZm0yZHFveFh3VVJkTHc9PSIsIm4iOjMyLCJtaW4iOjAsIm1heCI6MjU1LCJyZXBsYWNlbWVudCI6dHJ1ZSwiYmFzZSI6MTAsInByZWdlbmVyYXRlZFJhbmRvbWl6YXRpb24iOm51bGwsImRhdGEiOlszNCwxNTIsMTIyLDEyLDExOCw1MywxOTAsMzcsMjQsMTAsMCwxMDEsNjAsMTQ0LDE4NywxMSwxNzcsMTE0LDM3LDIxNywxNDksMjI0LDI2LDg3LDI5LDE0OSwxNTAsNzQsMTM2LDQ1LDE5OSwyMzJdLCJsaWNlbnNlIjp7InR5cGUiOiJkZXZlbG9wZXIiLCJ0ZXh0IjoiUmFuZG9tIHZhbHVlcyBsaWNlbnNlZCBzdHJpY3RseSBmb3IgZGV2ZWxvcG1lbnQgYW5kIHRlc3Rpbmcgb25seSIsImluZm9VcmwiOm51bGx9LCJsaWNlbnNlRGF0YSI6bnVsbCwidXNlckRhdGEiOm51bGwsInRpY2tldERhdGEiOm51bGwsImNvbXBsZXRpb25UaW1lIjoiMjAyMi0xMi0wOCAwMjo1MjoxNVoiLCJzZXJpYWxOdW1iZXIiOjl9";
+    const SIGNATURE: &str = "0K510lwXPxj8AHPV+cQoYuW4snOtjd8NTytz16XC8PHSOMXJNOW3yVynSiuVf20mc1fLHbmKjP08//TfqPyIYWd40A9OA+iJcHz+VXRgwCzSH/RK2nnxqN7uuah2xCXXerfcW5g/sRkRHrPZIjoTPVR/adXdjZBQ6q4Wb0JXItYpFv5aUCEBQWa2izq7Ax+ZNZI0PjifI5zQacPheVxoyEGYB2TtsWWYIHDI+M5afK0E0yyOjiR+emozmD3M3KgLpYq8UkaGR4rSNNgNsrLTyupDebOouRlyevXmKZURWmXZnJlW8sJKrvvPGnUQrRSDbpxBOuaBpg0SPozIr8Avv1CJCngcaDumjQFCuesQvTjQACBwrsqGZoSSHtw3QgWQdcfPnZBWOQ3jlaVk897fCEI6TYOnT+U9spvFmVdtmSVhaeftmQ5+yDoYhe5YHf2AQcmUxlikyhBmob4Fv6VDmKgpy5Ke30zlaNhFdXonvQZk+wlqlsaYk7cnmtaxrMcHlQcpVHZRRLNc5FHg0nFepe0z/T30XmFEyyOQlrAmpwZ6tKwksXDykQW5AyPUY6+esCl3rDXdt3GFis8D6/WldOKuMiGKW/JN7w9zR8W7NGxJ4INv3eO7Er8yJoxyMvD6eQ3STO3pAjBZ37e43mx7F/pnxaFOPPFrk9dMcWdCPmw=";
+
+    // SubjectPublicKeyInfo of a 1024 bit test key
+    const TEST_PUBLIC_KEY: &str = "-----BEGIN PUBLIC KEY-----
+MIGfMA0GCSqGSIb3DQEBAQUAA4GNADCBiQKBgQDxkCP68g9yPQzUo7vvlIydnqg6
+ziS3xrj/Rvw0gD3bv2rQqRg6LIXmW3OUsaSyZLh/TNcm6RLRxrUrEEXoeWHBddgy
+DkgrMlDToK2wgtrsgMyqFVwPf8uQOr4WWhE9MN0oVJO3o6o8qeb962YQ+eOXh3g2
+ErChONYvHYGCXY5egwIDAQAB
+-----END PUBLIC KEY-----";
+
+    #[test]
+    fn verify_random_org_signature_works() {
+        let public_key = public_key_from_components(RANDOM_ORG_PUBLIC_KEY_N, RANDOM_ORG_PUBLIC_KEY_E).unwrap();
+        let data = base64::decode(RANDOM_OBJ).unwrap();
+        let signature = base64::decode(SIGNATURE).unwrap();
+
+        assert_eq!(verify_pkcs1v15_sha512(&public_key, &data, &signature), Ok(()));
+
+        let mut tampered = data.clone();
+        tampered[10] ^= 1;
+        assert_eq!(verify_pkcs1v15_sha512(&public_key, &tampered, &signature), Err(RsaError::VerificationFailed{}));
+
+        // malformed signatures fail instead of panicking
+        assert_eq!(verify_pkcs1v15_sha512(&public_key, &data, &[]), Err(RsaError::VerificationFailed{}));
+        assert_eq!(verify_pkcs1v15_sha512(&public_key, &data, &signature[1..]), Err(RsaError::VerificationFailed{}));
+
+        // a valid signature of another key
+        let other_key = public_key_from_pem(TEST_PUBLIC_KEY).unwrap();
+        assert_eq!(verify_pkcs1v15_sha512(&other_key, &data, &signature), Err(RsaError::VerificationFailed{}));
+    }
+
+    #[test]
+    fn public_key_encodings_agree() {
+        let from_pem = public_key_from_pem(TEST_PUBLIC_KEY).unwrap();
+
+        let base64_der: String = TEST_PUBLIC_KEY.lines().filter(|line| !line.starts_with("-----")).collect();
+        let from_der = public_key_from_der(&base64::decode(base64_der).unwrap()).unwrap();
+        assert_eq!(from_der, from_pem);
+
+        let (n, e) = public_key_components(&from_pem);
+        assert_eq!(e, "010001");
+        assert_eq!(public_key_from_components(&n, &e).unwrap(), from_pem);
+
+        assert!(matches!(public_key_from_pem("-----BEGIN PUBLIC KEY-----\n-----END PUBLIC KEY-----"), Err(RsaError::InvalidPublicKey{..})));
+        assert!(matches!(public_key_from_der(&[0x30, 0x00]), Err(RsaError::InvalidPublicKey{..})));
+    }
+
+    #[test]
+    fn public_key_from_components_rejects_invalid_keys() {
+        assert!(matches!(public_key_from_components("zz", RANDOM_ORG_PUBLIC_KEY_E), Err(RsaError::InvalidPublicKey{..})));
+        assert!(matches!(public_key_from_components("00", RANDOM_ORG_PUBLIC_KEY_E), Err(RsaError::InvalidPublicKey{..})));
+    }
+}
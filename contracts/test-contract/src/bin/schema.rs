@@ -3,4 +3,10 @@ use cosmwasm_schema::write_api;
 use test_contract::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
 
 fn main() {
+    write_api! {
+        instantiate: InstantiateMsg,
+        migrate: MigrateMsg,
+        execute: ExecuteMsg,
+        query: QueryMsg,
+    }
 }
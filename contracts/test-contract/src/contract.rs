@@ -1,7 +1,7 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
-use cosmwasm_std::{to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Reply,
-     Response, StdResult, Addr, WasmMsg};
+use cosmwasm_std::{to_json_binary, Binary, Deps, DepsMut, Env, MessageInfo, Reply,
+     Response, StdResult, WasmMsg};
 use cw2::set_contract_version;
 
 use crate::error::ContractError;
@@ -33,7 +33,6 @@ pub fn instantiate(
 /// To make a contract migratable, you need
 /// - this entry_point implemented
 /// - only contract admin can migrate, so admin has to be set at contract initiation time
-/// Handling contract execution
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn migrate(_deps: DepsMut, _env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
     match msg {
@@ -57,30 +56,34 @@ pub fn execute(
         //
         // With `Response` type, it is possible to dispatch message to invoke external logic.
         // See: https://github.com/CosmWasm/cosmwasm/blob/main/SEMANTICS.md#dispatching-messages
-        ExecuteMsg::RequestRandomness{} => handle_request_randomness(),
-        ExecuteMsg::ReturnRandomness{randomness} => handle_return_randomness(_deps,_info,randomness),
+        ExecuteMsg::RequestRandomness{} => handle_request_randomness(_info),
+        ExecuteMsg::ReturnRandomness{randomness, ..} => handle_return_randomness(_deps,_info,randomness),
     }
 }
 
-fn handle_request_randomness() -> Result<Response, ContractError> {
+fn handle_request_randomness(_info: MessageInfo) -> Result<Response, ContractError> {
     let random_source = "aura1gjfcv5gvqd0j5x5rc6t9qdsjp4mj2s4msk0huk2yfqcfvndz49es7pzzpd";
 
     let request_randomness_msg  = CallBackMsg::RequestRandomness{
-        key_hash: "aabbccddeeff".to_string(),
+        user_seed: Binary::from(b"test-contract"),
+        callback_data: Binary::default(),
         time_set: 1000,
+        num_words: 1,
+        callback_gas_limit: Some(200_000),
     };
 
-    WasmMsg::Execute {
+    let _request = WasmMsg::Execute {
         contract_addr: random_source.to_string(),
-        msg: to_binary(&request_randomness_msg)?,
-        funds: vec![],
+        msg: to_json_binary(&request_randomness_msg)?,
+        // the request fee is paid by the caller
+        funds: _info.funds,
     };
-    return Ok(Response::new().add_attribute("action","request randomness".to_string()));
+    Ok(Response::new().add_attribute("action","request randomness".to_string()))
 }
 
-fn handle_return_randomness(_deps: DepsMut, _info: MessageInfo, randomness: String) -> Result<Response, ContractError> {
-    RANDOMNESS.save(_deps.storage, &randomness);
-    return Ok(Response::new().add_attribute("action","return randomness"));
+fn handle_return_randomness(_deps: DepsMut, _info: MessageInfo, randomness: Vec<String>) -> Result<Response, ContractError> {
+    RANDOMNESS.save(_deps.storage, &randomness)?;
+    Ok(Response::new().add_attribute("action","return randomness"))
 }
 
 /// Handling contract query
@@ -91,14 +94,14 @@ pub fn query(_deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
         // and then construct your query response with the type usually defined
         // `msg.rs` alongside with the query message itself.
         //
-        // use `cosmwasm_std::to_binary` to serialize query response to json binary.
-        QueryMsg::GetRandomness{} => to_binary(&query_randomness(_deps)?),
+        // use `cosmwasm_std::to_json_binary` to serialize query response to json binary.
+        QueryMsg::GetRandomness{} => to_json_binary(&query_randomness(_deps)?),
     }
 }
 
-fn query_randomness(_deps: Deps) -> StdResult<String>{
+fn query_randomness(_deps: Deps) -> StdResult<Vec<String>>{
     let randomness = RANDOMNESS.load(_deps.storage)?;
-    return Ok(randomness);
+    Ok(randomness)
 }
 
 /// Handling submessage reply.
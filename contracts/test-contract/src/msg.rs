@@ -1,4 +1,5 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::Binary;
 
 /// Message type for `instantiate` entry_point
 #[cw_serde]
@@ -8,7 +9,7 @@ pub struct InstantiateMsg {}
 #[cw_serde]
 pub enum ExecuteMsg {
     RequestRandomness{},
-    ReturnRandomness{randomness:String}
+    ReturnRandomness{randomness:Vec<String>, callback_data:Binary}
 }
 
 /// Message type for `migrate` entry_point
@@ -17,6 +18,7 @@ pub enum MigrateMsg {}
 
 /// Message type for `query` entry_point
 #[cw_serde]
+#[derive(QueryResponses)]
 pub enum QueryMsg {
     // This example query variant indicates that any client can query the contract
     // using `YourQuery` and it will return `YourQueryResponse`
@@ -25,13 +27,14 @@ pub enum QueryMsg {
     //
     // #[returns(YourQueryResponse)]
     // YourQuery {},
+    #[returns(Vec<String>)]
     GetRandomness{},
 }
 
 
 #[cw_serde]
 pub enum CallBackMsg {
-    RequestRandomness{key_hash:String,time_set:u128},
+    RequestRandomness{user_seed:Binary,callback_data:Binary,time_set:u128,num_words:u32,callback_gas_limit:Option<u64>},
 }
 // We define a custom struct for each query response
 // #[cw_serde]
@@ -1,3 +1,3 @@
 use cw_storage_plus::{Item};
 
-pub const RANDOMNESS: Item<String> = Item::new("randomness");
\ No newline at end of file
+pub const RANDOMNESS: Item<Vec<String>> = Item::new("randomness");
\ No newline at end of file
@@ -1,7 +1,9 @@
-use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::Binary;
 
 #[cw_serde]
 pub enum CallBackMsg {
-    ReturnRandomness{randomness:String},
+    /// Hex encoded 32 byte words, as many as requested, and the `callback_data` of the request
+    ReturnRandomness{randomness:Vec<String>, callback_data:Binary},
 }
 
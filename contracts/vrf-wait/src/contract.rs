@@ -1,20 +1,32 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
-use cosmwasm_std::{CosmosMsg, Binary, Deps, DepsMut, Env, MessageInfo, 
-    Reply, Response, StdResult, WasmMsg, to_binary, Empty};
+use cosmwasm_std::{Addr, BankMsg, Binary, BlockInfo, Coin, Decimal, Deps, DepsMut, Empty, Env, Event, MessageInfo, Order,
+    Reply, Response, StdResult, Storage, SubMsg, SubMsgResult, Timestamp, Uint128, WasmMsg, coins, to_json_binary};
+use cw_storage_plus::Bound;
 use cw2::set_contract_version;
+use generator_staking::{bonded_stake, slash, start_unbonding, treasury_payout, validate_staking_config, validate_staking_update};
 
 use crate::error::ContractError;
-use crate::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, DrandResponse};
-use crate::state::{Generator,RandomnessRequest,GENERATORS,RANDOMNESS_REQUEST_STATE};
+use crate::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, DrandResponse, RequestRandomnessResponse, SignedPush,
+    CommitmentsResponse, GeneratorsResponse, RandomnessResultResponse, RequestsResponse};
+use crate::state::{Commitment,Committee,CommitRevealConfig,CommitRevealWindow,Config,Delivery,DrandConfig,Expiry,FulfilledResult,Generator,Offence,
+    RandomnessMode,RandomnessRequest,RequestStatus,StakingConfig,Unbonding,
+    COMMITMENTS,COMMITTEE,CONFIG,CONSUMED_ROUNDS,EXPIRIES_BY_HEIGHT,EXPIRIES_BY_TIME,FULFILLED_RESULTS,GENERATORS,LAST_ASSIGNED_GENERATOR,
+    OFFENCES,PUBLIC_KEYS,RANDOMNESS_REQUESTS,REQUEST_COUNT,UNBONDINGS,USED_PROOFS};
 use crate::hasher::sha256_hash;
 use crate::call_back::{CallBackMsg};
-use crate::drand_verify::verify_drand_randomness;
+use crate::drand_verify::{decode_g1_public_key, decode_public_key, derive_randomness_from_signature, round_after, round_time,
+    verify_bls_signature, verify_drand_randomness, DrandError};
+use crate::ecvrf;
 
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:vrf-wait";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+// pagination info for list queries
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+
 /// Handling contract instantiation
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
@@ -25,6 +37,28 @@ pub fn instantiate(
 ) -> Result<Response, ContractError> {
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
+    validate_drand_config(&_msg.drand)?;
+    validate_request_fee(&_msg.request_fee)?;
+    validate_staking_config(deps.api, &_msg.staking)?;
+    validate_reporter_reward(_msg.reporter_reward)?;
+    validate_commit_reveal_config(&_msg.commit_reveal)?;
+    validate_request_timeout(_msg.request_timeout)?;
+    validate_max_num_words(_msg.max_num_words)?;
+    validate_max_callback_gas_limit(_msg.max_callback_gas_limit)?;
+
+    CONFIG.save(deps.storage, &Config{
+        owner: info.sender.clone(),
+        drand: _msg.drand,
+        request_fee: _msg.request_fee,
+        staking: _msg.staking,
+        reporter_reward: _msg.reporter_reward,
+        commit_reveal: _msg.commit_reveal,
+        request_timeout: _msg.request_timeout,
+        max_num_words: _msg.max_num_words,
+        max_callback_gas_limit: _msg.max_callback_gas_limit,
+    })?;
+    REQUEST_COUNT.save(deps.storage, &0)?;
+
     // With `Response` type, it is possible to dispatch message to invoke external logic.
     // See: https://github.com/CosmWasm/cosmwasm/blob/main/SEMANTICS.md#dispatching-messages
     Ok(Response::new()
@@ -36,7 +70,6 @@ pub fn instantiate(
 /// To make a contract migratable, you need
 /// - this entry_point implemented
 /// - only contract admin can migrate, so admin has to be set at contract initiation time
-/// Handling contract execution
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn migrate(_deps: DepsMut, _env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
     match msg {
@@ -60,332 +93,3299 @@ pub fn execute(
         //
         // With `Response` type, it is possible to dispatch message to invoke external logic.
         // See: https://github.com/CosmWasm/cosmwasm/blob/main/SEMANTICS.md#dispatching-messages
-        ExecuteMsg::PushRandomness{randomness,signature,drand_response} => handle_push_randomness(_deps,_info,randomness,signature,drand_response),
-        ExecuteMsg::RequestRandomness{key_hash, time_set} => handle_request_randomness(_deps,_info,key_hash,time_set),
+        ExecuteMsg::PushRandomness{proof,drand_response,request_id} => handle_push_randomness(_deps,_env,_info,proof,drand_response,request_id),
+        ExecuteMsg::RequestRandomness{user_seed, callback_data, time_set, num_words, mode, delivery, expires, callback_contract,
+            callback_gas_limit} => handle_request_randomness(_deps,_env,_info,RequestParams{user_seed,callback_data,time_set,num_words,
+                mode,delivery,expires,callback_contract,callback_gas_limit}),
         ExecuteMsg::Register{public_key} => handle_register(_deps,_info,public_key),
-        ExecuteMsg::DeleteGenerator{} => handle_delete_generator(_deps,_info),
+        ExecuteMsg::DeleteGenerator{} => handle_delete_generator(_deps,_env,_info),
+        ExecuteMsg::ClaimUnbonded{} => handle_claim_unbonded(_deps,_env,_info),
+        ExecuteMsg::UpdateDrandConfig{drand} => handle_update_drand_config(_deps,_info,drand),
+        ExecuteMsg::UpdateRequestFee{request_fee} => handle_update_request_fee(_deps,_info,request_fee),
+        ExecuteMsg::UpdateStakingConfig{staking} => handle_update_staking_config(_deps,_info,staking),
+        ExecuteMsg::UpdateReporterReward{reporter_reward} => handle_update_reporter_reward(_deps,_info,reporter_reward),
+        ExecuteMsg::SubmitEvidence{generator, drand_response, first, second} => handle_submit_evidence(_deps,_env,_info,generator,drand_response,first,second),
+        ExecuteMsg::UpdateCommitRevealConfig{commit_reveal} => handle_update_commit_reveal_config(_deps,_info,commit_reveal),
+        ExecuteMsg::UpdateRequestTimeout{request_timeout} => handle_update_request_timeout(_deps,_info,request_timeout),
+        ExecuteMsg::UpdateMaxNumWords{max_num_words} => handle_update_max_num_words(_deps,_info,max_num_words),
+        ExecuteMsg::UpdateMaxCallbackGasLimit{max_callback_gas_limit} => handle_update_max_callback_gas_limit(_deps,_info,max_callback_gas_limit),
+        ExecuteMsg::SweepExpired{limit} => handle_sweep_expired(_deps,_env,limit),
+        ExecuteMsg::Commit{request_id, commitment} => handle_commit(_deps,_env,_info,request_id,commitment),
+        ExecuteMsg::Reveal{request_id, secret} => handle_reveal(_deps,_env,_info,request_id,secret),
+        ExecuteMsg::FinalizeCommitReveal{request_id, drand_response} => handle_finalize_commit_reveal(_deps,_env,_info,request_id,drand_response),
+        ExecuteMsg::RegisterCommittee{public_key, members, threshold} => handle_register_committee(_deps,_info,public_key,members,threshold),
+        ExecuteMsg::PushThresholdSignature{request_id, signature} => handle_push_threshold_signature(_deps,_env,_info,request_id,signature),
+        ExecuteMsg::CancelRequest{id} => handle_cancel_request(_deps,_env,_info,id),
     }
 }
 
-fn handle_delete_generator(_deps: DepsMut, _info: MessageInfo) -> Result<Response, ContractError> {
-    if !GENERATORS.has(_deps.storage, _info.sender.clone()) {
-        return Err(ContractError::CustomError{val:"Unregisterd address!".to_string()}); 
+fn validate_drand_config(drand: &DrandConfig) -> Result<(), ContractError> {
+    decode_public_key(&drand.scheme, &drand.public_key)?;
+
+    let chain_hash = decode_hex("chain_hash", &drand.chain_hash)?;
+    if chain_hash.len() != 32 {
+        return Err(ContractError::InvalidLength{field: "chain_hash".to_string(), expected: 32, actual: chain_hash.len()});
     }
 
-    GENERATORS.remove(_deps.storage, _info.sender);
+    if drand.period == 0 {
+        return Err(ContractError::InvalidDrandPeriod{});
+    }
 
-    return Ok(Response::new().add_attribute("action","delete generator!".to_string()));
+    Ok(())
 }
 
+fn handle_update_drand_config(_deps: DepsMut, _info: MessageInfo, drand: DrandConfig) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(_deps.storage)?;
 
-fn handle_register(_deps: DepsMut, _info: MessageInfo, public_key: String) -> Result<Response, ContractError>{
+    if _info.sender != config.owner {
+        return Err(ContractError::Unauthorized{});
+    }
 
-    if GENERATORS.has(_deps.storage, _info.sender.clone()) {
-        return Err(ContractError::CustomError{val:"Address has been registered !".to_string()}); 
+    validate_drand_config(&drand)?;
+
+    config.drand = drand;
+    CONFIG.save(_deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "update drand config")
+        .add_attribute("chain_hash", config.drand.chain_hash))
+}
+
+fn validate_request_fee(request_fee: &Option<Coin>) -> Result<(), ContractError> {
+    match request_fee {
+        Some(fee) if fee.amount.is_zero() => Err(ContractError::MustNotBeZero{field: "request_fee".to_string()}),
+        _ => Ok(()),
+    }
+}
+
+fn handle_update_request_fee(_deps: DepsMut, _info: MessageInfo, request_fee: Option<Coin>) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(_deps.storage)?;
+
+    if _info.sender != config.owner {
+        return Err(ContractError::Unauthorized{});
     }
 
-    GENERATORS.save(_deps.storage, _info.sender.clone(), &Generator{
-        addr: _info.sender.clone(),
-        public_key: public_key,
-    })?;
+    validate_request_fee(&request_fee)?;
 
-    return Ok(Response::new()
-        .add_attribute("action", "register".to_string())
-    );
+    config.request_fee = request_fee;
+    CONFIG.save(_deps.storage, &config)?;
+
+    Ok(Response::new().add_attribute("action", "update request fee"))
 }
 
-fn handle_request_randomness(_deps: DepsMut, _info: MessageInfo, key_hash: String, 
-    time_set: u128) -> Result<Response, ContractError> {
-    let request = RandomnessRequest {
-        user: _info.sender,
-        key_hash: key_hash,
-        time: time_set,
-    };
+/// Checks that `funds` pay exactly the request fee and returns the coins to escrow
+fn escrow_request_fee(request_fee: &Option<Coin>, funds: &[Coin]) -> Result<Vec<Coin>, ContractError> {
+    match request_fee {
+        Some(fee) if funds.len() == 1 && funds[0] == *fee => Ok(funds.to_vec()),
+        Some(fee) => Err(ContractError::InvalidRequestFee{expected: fee.to_string()}),
+        None if funds.is_empty() => Ok(vec![]),
+        None => Err(ContractError::InvalidRequestFee{expected: "nothing".to_string()}),
+    }
+}
+
+/// Message paying out the escrow of a request, if there is any
+fn release_escrow(request: &RandomnessRequest, to_address: &str) -> Option<BankMsg> {
+    if request.escrow.is_empty() {
+        return None;
+    }
 
-    RANDOMNESS_REQUEST_STATE.push_back(_deps.storage,&request)?;
+    Some(BankMsg::Send {
+        to_address: to_address.to_string(),
+        amount: request.escrow.clone(),
+    })
+}
 
-    return Ok(Response::new().add_attribute("action","request randomness"));
+fn validate_request_timeout(request_timeout: Option<u64>) -> Result<(), ContractError> {
+    match request_timeout {
+        Some(0) => Err(ContractError::MustNotBeZero{field: "request_timeout".to_string()}),
+        _ => Ok(()),
+    }
 }
 
+fn handle_update_request_timeout(_deps: DepsMut, _info: MessageInfo, request_timeout: Option<u64>) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(_deps.storage)?;
 
-fn handle_push_randomness(_deps: DepsMut, _info: MessageInfo, randomness: String, 
-    signature: String, drand_response: DrandResponse) -> Result<Response, ContractError>{
-    
-    if !GENERATORS.has(_deps.storage, _info.sender.clone()){
-        return Err(ContractError::CustomError{val:"Unregistered adrress!".to_string()});
+    if _info.sender != config.owner {
+        return Err(ContractError::Unauthorized{});
     }
 
-    let generator = GENERATORS.load(_deps.storage, _info.sender.clone())?;
+    validate_request_timeout(request_timeout)?;
+    config.request_timeout = request_timeout;
+    CONFIG.save(_deps.storage, &config)?;
 
-    if verify_drand_randomness(drand_response.round, 
-                            hex::decode(drand_response.signature).unwrap(),
-                            hex::decode(drand_response.previous_signature).unwrap()) {
-        
-        let signature_bytes = hex::decode(signature.clone()).unwrap();
-        let randomness_bytes = hex::decode(randomness.clone()).unwrap();
-        let key_bytes = hex::decode(generator.public_key.clone()).unwrap();
+    Ok(Response::new().add_attribute("action", "update request timeout"))
+}
 
-        let result = _deps.api.secp256k1_verify(&randomness_bytes, &signature_bytes, &key_bytes);
+fn validate_max_num_words(max_num_words: u32) -> Result<(), ContractError> {
+    if max_num_words == 0 {
+        return Err(ContractError::MustNotBeZero{field: "max_num_words".to_string()});
+    }
 
-        match result {
-            Ok(true) => {             
-                let request_op = RANDOMNESS_REQUEST_STATE.pop_back(_deps.storage)?;
+    Ok(())
+}
 
-                if request_op.is_some() {
-                    let request = request_op.unwrap();
+fn handle_update_max_num_words(_deps: DepsMut, _info: MessageInfo, max_num_words: u32) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(_deps.storage)?;
 
-                    let seed = signature + &request.key_hash + &hex::encode(request.user.as_bytes());
+    if _info.sender != config.owner {
+        return Err(ContractError::Unauthorized{});
+    }
 
-                    let return_randomness = sha256_hash(&hex::decode(seed).unwrap());
+    validate_max_num_words(max_num_words)?;
+    config.max_num_words = max_num_words;
+    CONFIG.save(_deps.storage, &config)?;
 
-                    let return_randomness_msg  = CallBackMsg::ReturnRandomness{
-                        randomness: hex::encode(return_randomness),
-                    };
+    Ok(Response::new().add_attribute("action", "update max num words"))
+}
 
-                    WasmMsg::Execute {
-                        contract_addr: request.user.as_ref().to_string(),
-                        msg: to_binary(&return_randomness_msg)?,
-                        funds: vec![],
-                    };
-                }
-                    
-            },
-            Ok(false) => return Err(ContractError::CustomError{val:"Invalid generator signature!".to_string()}),
-            Err(_err) => return Err(ContractError::CustomError{val:"Invalid generator signature!".to_string()}),
-        }
-    }else {
-        return Err(ContractError::CustomError{val:"Invalid drand randomness!".to_string()});
+fn validate_max_callback_gas_limit(max_callback_gas_limit: u64) -> Result<(), ContractError> {
+    if max_callback_gas_limit == 0 {
+        return Err(ContractError::MustNotBeZero{field: "max_callback_gas_limit".to_string()});
     }
 
-    return Ok(Response::new().add_attribute("action","push randomness".to_string()));
+    Ok(())
 }
 
-/// Handling contract query
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(_deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
-    match msg {
-        // Find matched incoming message variant and query them your custom logic
-        // and then construct your query response with the type usually defined
-        // `msg.rs` alongside with the query message itself.
-        //
-        // use `cosmwasm_std::to_binary` to serialize query response to json binary.
+fn handle_update_max_callback_gas_limit(_deps: DepsMut, _info: MessageInfo, max_callback_gas_limit: u64) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(_deps.storage)?;
+
+    if _info.sender != config.owner {
+        return Err(ContractError::Unauthorized{});
     }
+
+    validate_max_callback_gas_limit(max_callback_gas_limit)?;
+    config.max_callback_gas_limit = max_callback_gas_limit;
+    CONFIG.save(_deps.storage, &config)?;
+
+    Ok(Response::new().add_attribute("action", "update max callback gas limit"))
 }
 
-/// Handling submessage reply.
-/// For more info on submessage and reply, see https://github.com/CosmWasm/cosmwasm/blob/main/SEMANTICS.md#submessages
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn reply(_deps: DepsMut, _env: Env, _msg: Reply) -> Result<Response, ContractError> {
-    // With `Response` type, it is still possible to dispatch message to invoke external logic.
-    // See: https://github.com/CosmWasm/cosmwasm/blob/main/SEMANTICS.md#dispatching-messages
+/// Fails unless `request` is pending and not expired
+fn ensure_fulfillable(request: &RandomnessRequest, block: &BlockInfo) -> Result<(), ContractError> {
+    if request.status != RequestStatus::Pending {
+        return Err(ContractError::RequestNotPending{id: request.id});
+    }
+
+    if request.expires.as_ref().is_some_and(|expires| expires.is_expired(block)) {
+        return Err(ContractError::RequestExpired{id: request.id});
+    }
 
-    todo!()
+    Ok(())
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use cosmwasm_std::testing::{
-        mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
-    };
-    use cosmwasm_std::{
-        from_slice, Binary, OwnedDeps, RecoverPubkeyError, StdError, VerificationError, Env,
-    };
+fn handle_sweep_expired(_deps: DepsMut, _env: Env, limit: Option<u32>) -> Result<Response, ContractError> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
 
-    const CREATOR: &str = "creator";
-    const GENERATOR: &str = "generator";
-    const USER: &str = "user";
+    // every due entry counts against the limit, also those of requests that are no longer pending
+    let mut due: Vec<(u64, u64)> = EXPIRIES_BY_HEIGHT
+        .keys(_deps.storage, None, Some(Bound::inclusive((_env.block.height, u64::MAX))), Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<_>>()?;
+    for key in due.iter() {
+        EXPIRIES_BY_HEIGHT.remove(_deps.storage, *key);
+    }
 
-    fn setup() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
-        let mut deps = mock_dependencies();
-        let msg = InstantiateMsg {};
-        let info = mock_info(CREATOR, &[]);
-        let res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
-        assert_eq!(0, res.messages.len());
-        deps
+    let due_by_time: Vec<(u64, u64)> = EXPIRIES_BY_TIME
+        .keys(_deps.storage, None, Some(Bound::inclusive((_env.block.time.nanos(), u64::MAX))), Order::Ascending)
+        .take(limit - due.len())
+        .collect::<StdResult<_>>()?;
+    for key in due_by_time.iter() {
+        EXPIRIES_BY_TIME.remove(_deps.storage, *key);
     }
+    due.extend(due_by_time);
 
-    #[test]
-    fn instantiate_works() {
-        setup();
+    let mut response = Response::new().add_attribute("action", "sweep expired");
+    let mut expired = 0;
+
+    for (_, id) in due {
+        let mut request = RANDOMNESS_REQUESTS.load(_deps.storage, id)?;
+        if request.status != RequestStatus::Pending {
+            continue;
+        }
+
+        request.status = RequestStatus::Expired;
+        RANDOMNESS_REQUESTS.save(_deps.storage, id, &request)?;
+        expired += 1;
+
+        response = response
+            .add_messages(release_escrow(&request, request.user.as_str()))
+            .add_event(Event::new("request_expired")
+                .add_attribute("request_id", id.to_string())
+                .add_attribute("user", request.user));
     }
 
-    #[test]
-    fn generator_register_success() {
-        let mut deps = setup();
+    Ok(response.add_attribute("expired", expired.to_string()))
+}
 
-        let public_key: String = "03224d723fb48f1c247e105bca7853fb5c711e59fc824f5e581614d80bfbde1c99".to_string();
+fn handle_cancel_request(_deps: DepsMut, _env: Env, _info: MessageInfo, id: u64) -> Result<Response, ContractError> {
+    let mut request = RANDOMNESS_REQUESTS.may_load(_deps.storage, id)?
+        .ok_or(ContractError::RequestNotFound{id})?;
 
-        let register_msg = ExecuteMsg::Register{
-            public_key
-        };
+    if request.user != _info.sender {
+        return Err(ContractError::Unauthorized{});
+    }
 
-        let raw = execute(deps.as_mut(), mock_env(), mock_info(GENERATOR, &[]), register_msg).unwrap();
+    if request.status != RequestStatus::Pending {
+        return Err(ContractError::RequestNotPending{id});
+    }
 
-        assert_eq!(raw, Response::new().add_attribute("action", "register".to_string()))
+    // a requester who could foresee the result would cancel the ones it dislikes
+    let cancellable = match &request.commit_reveal {
+        Some(window) => _env.block.time < window.commit_deadline
+            && COMMITMENTS.prefix(id).keys_raw(_deps.storage, None, None, Order::Ascending).next().is_none(),
+        None => {
+            let drand = CONFIG.load(_deps.storage)?.drand;
+            _env.block.time.seconds() < round_time(drand.genesis_time, drand.period, request.min_round)
+        },
+    };
+    if !cancellable {
+        return Err(ContractError::RequestNotCancellable{id});
     }
 
-    #[test]
-    fn generator_push_randomness_success() {
-        let mut deps = setup();
+    let refund = release_escrow(&request, request.user.as_str());
 
-        let public_key: String = "03224d723fb48f1c247e105bca7853fb5c711e59fc824f5e581614d80bfbde1c99".to_string();
-        
-        let register_msg = ExecuteMsg::Register{
-            public_key
-        };
+    request.status = RequestStatus::Cancelled;
+    RANDOMNESS_REQUESTS.save(_deps.storage, id, &request)?;
 
-        let raw = execute(deps.as_mut(), mock_env(), mock_info(GENERATOR, &[]), register_msg).unwrap();
+    Ok(Response::new()
+        .add_messages(refund)
+        .add_attribute("action", "cancel request")
+        .add_attribute("request_id", id.to_string()))
+}
 
-        assert_eq!(raw, Response::new().add_attribute("action", "register".to_string()));
+fn handle_update_staking_config(_deps: DepsMut, _info: MessageInfo, staking: StakingConfig) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(_deps.storage)?;
 
-        let randomness = "3e145797dba4b22ace82d72ddcd2c4fede803247337ba8b11fca7e2402e235a9".to_string();
-        let signature = "d92ae4131cbd4e720789ad13fef129d6043421301ba40342513a550b10b7219b6b6d916a711a660bdde1b88a55933ac324687d3b7240404146d4c13f814a99c1".to_string();
-        let drand_response = DrandResponse {
-            round: 2515680,
-            signature: "b46015d219fb64ad572c32ae5104e3d6498ea9a7e8045d5f7b1355f20b3c05894f7c9b7af2a5d4499dc6c8f3a50f85d209b903e2c0660075a994e463c99c49582eab9d991a35fba2784979f51a5943476a3cf2096762be81f2d90a9bc1097259".to_string(),
-            previous_signature: "b70285d05e8021c296f4e1fc4f7624210a5540a59628df703bcceebb7f5df9c1ce205956c23a5ec7bc31bc431535b53007d2abb32a2a84f4d1318aba0cfa9088d6a11aac135c56e77b14ed0ee2247084066a1590bec22f1e0802460ce18d21ba".to_string(),
-        };
+    if _info.sender != config.owner {
+        return Err(ContractError::Unauthorized{});
+    }
 
-        let push_randomness_msg = ExecuteMsg::PushRandomness {
-            randomness,
-            signature,
-            drand_response
-        };
+    validate_staking_update(_deps.api, &config.staking, &staking)?;
 
-        let raw_push = execute(deps.as_mut(), mock_env(), mock_info(GENERATOR, &[]), push_randomness_msg).unwrap();
+    config.staking = staking;
+    CONFIG.save(_deps.storage, &config)?;
 
-        assert_eq!(raw_push, Response::new().add_attribute("action", "push randomness".to_string()));
+    Ok(Response::new().add_attribute("action", "update staking config"))
+}
 
+fn validate_reporter_reward(reporter_reward: Decimal) -> Result<(), ContractError> {
+    if reporter_reward > Decimal::one() {
+        return Err(ContractError::InvalidReporterReward{});
     }
 
-    #[test]
-    fn generator_push_randomness_fail_with_invalid_drand_randomness() {
-        let mut deps = setup();
+    Ok(())
+}
 
-        let public_key: String = "03224d723fb48f1c247e105bca7853fb5c711e59fc824f5e581614d80bfbde1c99".to_string();
-        
-        let register_msg = ExecuteMsg::Register{
-            public_key
-        };
+fn handle_update_reporter_reward(_deps: DepsMut, _info: MessageInfo, reporter_reward: Decimal) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(_deps.storage)?;
 
-        let raw = execute(deps.as_mut(), mock_env(), mock_info(GENERATOR, &[]), register_msg).unwrap();
+    if _info.sender != config.owner {
+        return Err(ContractError::Unauthorized{});
+    }
 
-        assert_eq!(raw, Response::new().add_attribute("action", "register".to_string()));
+    validate_reporter_reward(reporter_reward)?;
 
-        let randomness = "3e145797dba4b22ace82d72ddcd2c4fede803247337ba8b11fca7e2402e235a9".to_string();
-        let signature = "d92ae4131cbd4e720789ad13fef129d6043421301ba40342513a550b10b7219b6b6d916a711a660bdde1b88a55933ac324687d3b7240404146d4c13f814a99c1".to_string();
-        let drand_response = DrandResponse {
-            round: 2515680,
-            signature: "a46015d219fb64ad572c32ae5104e3d6498ea9a7e8045d5f7b1355f20b3c05894f7c9b7af2a5d4499dc6c8f3a50f85d209b903e2c0660075a994e463c99c49582eab9d991a35fba2784979f51a5943476a3cf2096762be81f2d90a9bc1097259".to_string(),
-            previous_signature: "b70285d05e8021c296f4e1fc4f7624210a5540a59628df703bcceebb7f5df9c1ce205956c23a5ec7bc31bc431535b53007d2abb32a2a84f4d1318aba0cfa9088d6a11aac135c56e77b14ed0ee2247084066a1590bec22f1e0802460ce18d21ba".to_string(),
-        };
+    config.reporter_reward = reporter_reward;
+    CONFIG.save(_deps.storage, &config)?;
 
-        let push_randomness_msg = ExecuteMsg::PushRandomness {
-            randomness,
-            signature,
-            drand_response
-        };
+    Ok(Response::new().add_attribute("action", "update reporter reward"))
+}
 
-        let raw_push = execute(deps.as_mut(), mock_env(), mock_info(GENERATOR, &[]), push_randomness_msg).unwrap_err();
+/// Slashes `generator`, dropping it and freeing its public key once it no longer holds the
+/// minimum stake or when it is to be deregistered, the rest of its stake starts unbonding then
+fn slash_generator(storage: &mut dyn Storage, env: &Env, staking: &StakingConfig, mut generator: Generator, deregister: bool) -> StdResult<Uint128> {
+    let slash = slash(storage, env, staking, &generator.addr, generator.stake)?;
+
+    match slash.remaining {
+        Some(stake) if deregister => {
+            remove_generator(storage, &generator)?;
+            start_unbonding(storage, env, staking, &generator.addr, stake)?;
+        },
+        Some(stake) => {
+            generator.stake = stake;
+            GENERATORS.save(storage, generator.addr.clone(), &generator)?;
+        },
+        None => remove_generator(storage, &generator)?,
+    }
 
-        match raw_push {
-            ContractError::CustomError{val:a} => {assert_eq!(a, "Invalid drand randomness!".to_string());},
-            _ => panic!("")
+    Ok(slash.slashed)
+}
+
+/// Pays slashed stake to the treasury, less the reward of the reporter if there is one
+fn slash_payouts(config: &Config, slashed: Uint128, reporter: Option<&Addr>) -> Vec<BankMsg> {
+    let mut payouts = vec![];
+    let mut remaining = slashed;
+
+    if let Some(reporter) = reporter {
+        let reward = slashed.mul_floor(config.reporter_reward);
+        remaining -= reward;
+        if !reward.is_zero() {
+            payouts.push(BankMsg::Send {
+                to_address: reporter.to_string(),
+                amount: coins(reward.u128(), &config.staking.denom),
+            });
         }
     }
 
-    #[test]
-    fn generator_push_randomness_fail_with_invalid_generator_signature() {
-        let mut deps = setup();
+    payouts.extend(treasury_payout(&config.staking, remaining));
+    payouts
+}
 
-        let public_key: String = "03224d723fb48f1c247e105bca7853fb5c711e59fc824f5e581614d80bfbde1c99".to_string();
-        
-        let register_msg = ExecuteMsg::Register{
-            public_key
-        };
+/// Verifies a generator's proof for a request and returns its VRF output
+fn verify_signed_push(env: &Env, generator: &Generator, push: &SignedPush) -> Result<Vec<u8>, ContractError> {
+    let drand_randomness = decode_hex("drand_randomness", &push.drand_randomness)?;
+    let preimage = randomness_preimage(env, push.request_id, push.round, &drand_randomness);
+    Ok(ecvrf::verify(&generator.public_key, &preimage, &push.proof)?)
+}
 
-        let raw = execute(deps.as_mut(), mock_env(), mock_info(GENERATOR, &[]), register_msg).unwrap();
+fn handle_submit_evidence(_deps: DepsMut, _env: Env, _info: MessageInfo, generator: String,
+    drand_response: DrandResponse, first: SignedPush, second: SignedPush) -> Result<Response, ContractError> {
+    let generator_addr = _deps.api.addr_validate(&generator)?;
 
-        assert_eq!(raw, Response::new().add_attribute("action", "register".to_string()));
+    // the reporter reward would otherwise soften the generator's own penalty
+    if _info.sender == generator_addr {
+        return Err(ContractError::SelfReport{});
+    }
 
-        let randomness = "3e145797dba4b22ace82d72ddcd2c4fede803247337ba8b11fca7e2402e235a9".to_string();
-        let signature = "a92ae4131cbd4e720789ad13fef129d6043421301ba40342513a550b10b7219b6b6d916a711a660bdde1b88a55933ac324687d3b7240404146d4c13f814a99c1".to_string();
-        let drand_response = DrandResponse {
-            round: 2515680,
-            signature: "b46015d219fb64ad572c32ae5104e3d6498ea9a7e8045d5f7b1355f20b3c05894f7c9b7af2a5d4499dc6c8f3a50f85d209b903e2c0660075a994e463c99c49582eab9d991a35fba2784979f51a5943476a3cf2096762be81f2d90a9bc1097259".to_string(),
-            previous_signature: "b70285d05e8021c296f4e1fc4f7624210a5540a59628df703bcceebb7f5df9c1ce205956c23a5ec7bc31bc431535b53007d2abb32a2a84f4d1318aba0cfa9088d6a11aac135c56e77b14ed0ee2247084066a1590bec22f1e0802460ce18d21ba".to_string(),
-        };
+    let generator = GENERATORS.may_load(_deps.storage, generator_addr.clone())?
+        .ok_or(ContractError::GeneratorNotRegistered{})?;
 
-        let push_randomness_msg = ExecuteMsg::PushRandomness {
-            randomness,
-            signature,
-            drand_response
-        };
+    if first.request_id != second.request_id {
+        return Err(ContractError::EvidenceNotConflicting{});
+    }
 
-        let raw_push = execute(deps.as_mut(), mock_env(), mock_info(GENERATOR, &[]), push_randomness_msg).unwrap_err();
+    // only the generator assigned to an existing request can fulfil it with its round
+    let request = RANDOMNESS_REQUESTS.may_load(_deps.storage, first.request_id)?
+        .ok_or(ContractError::RequestNotFound{id: first.request_id})?;
+    if request.assigned_generator.as_ref() != Some(&generator_addr) {
+        return Err(ContractError::GeneratorNotAssigned{id: request.id});
+    }
+    if drand_response.round != request.min_round {
+        return Err(ContractError::EvidenceRoundMismatch{round: drand_response.round, min_round: request.min_round});
+    }
 
-        match raw_push {
-            ContractError::CustomError{val:a} => {assert_eq!(a, "Invalid generator signature!".to_string());},
-            _ => panic!("")
-        }
+    let key = (generator_addr.clone(), request.id);
+    if OFFENCES.has(_deps.storage, key.clone()) {
+        return Err(ContractError::OffenceAlreadyRecorded{generator: generator_addr.to_string(), request_id: request.id});
     }
 
-    #[test]
-    fn generator_push_randomness_fail_with_unregistered_address() {
-        let mut deps = setup();
+    let config = CONFIG.load(_deps.storage)?;
+    verify_drand_randomness(
+        &config.drand.scheme,
+        &config.drand.public_key,
+        drand_response.round,
+        &drand_response.signature,
+        drand_response.previous_signature.as_deref(),
+    )?;
+
+    // both proofs are for the beacon's round and at least one is over its genuine randomness
+    let drand_randomness = derive_randomness_from_signature(&decode_hex("signature", &drand_response.signature)?);
+    if first.round != drand_response.round || second.round != drand_response.round
+        || (decode_hex("drand_randomness", &first.drand_randomness)? != drand_randomness
+            && decode_hex("drand_randomness", &second.drand_randomness)? != drand_randomness) {
+        return Err(ContractError::EvidenceBeaconMismatch{round: drand_response.round});
+    }
 
-        let public_key: String = "03224d723fb48f1c247e105bca7853fb5c711e59fc824f5e581614d80bfbde1c99".to_string();
-        
-        let register_msg = ExecuteMsg::Register{
-            public_key
-        };
+    // VRF outputs are unique per input, only one input per request is genuine
+    let first_output = verify_signed_push(&_env, &generator, &first)?;
+    let second_output = verify_signed_push(&_env, &generator, &second)?;
+    if first_output == second_output {
+        return Err(ContractError::EvidenceNotConflicting{});
+    }
 
-        let raw = execute(deps.as_mut(), mock_env(), mock_info(GENERATOR, &[]), register_msg).unwrap();
+    let slashed = slash_generator(_deps.storage, &_env, &config.staking, generator, true)?;
 
-        assert_eq!(raw, Response::new().add_attribute("action", "register".to_string()));
+    OFFENCES.save(_deps.storage, key, &Offence {
+        generator: generator_addr.clone(),
+        request_id: request.id,
+        reporter: _info.sender.clone(),
+        slashed,
+        height: _env.block.height,
+    })?;
 
-        let randomness = "3e145797dba4b22ace82d72ddcd2c4fede803247337ba8b11fca7e2402e235a9".to_string();
-        let signature = "d92ae4131cbd4e720789ad13fef129d6043421301ba40342513a550b10b7219b6b6d916a711a660bdde1b88a55933ac324687d3b7240404146d4c13f814a99c1".to_string();
-        let drand_response = DrandResponse {
-            round: 2515680,
-            signature: "b46015d219fb64ad572c32ae5104e3d6498ea9a7e8045d5f7b1355f20b3c05894f7c9b7af2a5d4499dc6c8f3a50f85d209b903e2c0660075a994e463c99c49582eab9d991a35fba2784979f51a5943476a3cf2096762be81f2d90a9bc1097259".to_string(),
-            previous_signature: "b70285d05e8021c296f4e1fc4f7624210a5540a59628df703bcceebb7f5df9c1ce205956c23a5ec7bc31bc431535b53007d2abb32a2a84f4d1318aba0cfa9088d6a11aac135c56e77b14ed0ee2247084066a1590bec22f1e0802460ce18d21ba".to_string(),
-        };
+    Ok(Response::new()
+        .add_messages(slash_payouts(&config, slashed, Some(&_info.sender)))
+        .add_attribute("action", "slash generator")
+        .add_attribute("generator", generator_addr)
+        .add_attribute("reason", "equivocation")
+        .add_attribute("request_id", request.id.to_string())
+        .add_attribute("slashed", slashed)
+        .add_attribute("reporter", _info.sender))
+}
 
-        let push_randomness_msg = ExecuteMsg::PushRandomness {
-            randomness,
-            signature,
-            drand_response
-        };
+fn handle_delete_generator(_deps: DepsMut, _env: Env, _info: MessageInfo) -> Result<Response, ContractError> {
+    let generator = GENERATORS.may_load(_deps.storage, _info.sender.clone())?
+        .ok_or(ContractError::GeneratorNotRegistered{})?;
 
-        let raw_push = execute(deps.as_mut(), mock_env(), mock_info(CREATOR, &[]), push_randomness_msg).unwrap_err();
+    let staking = CONFIG.load(_deps.storage)?.staking;
 
-        match raw_push {
-            ContractError::CustomError{val:a} => {assert_eq!(a, "Unregistered adrress!".to_string());},
-            _ => panic!("")
-        }
+    remove_generator(_deps.storage, &generator)?;
+    start_unbonding(_deps.storage, &_env, &staking, &_info.sender, generator.stake)?;
+
+    Ok(Response::new().add_attribute("action","delete generator!".to_string()))
+}
+
+/// Drops a generator and frees its public key for registration
+fn remove_generator(storage: &mut dyn Storage, generator: &Generator) -> StdResult<()> {
+    GENERATORS.remove(storage, generator.addr.clone());
+    PUBLIC_KEYS.remove(storage, &generator.public_key);
+    Ok(())
+}
+
+fn handle_claim_unbonded(_deps: DepsMut, _env: Env, _info: MessageInfo) -> Result<Response, ContractError> {
+    let staking = CONFIG.load(_deps.storage)?.staking;
+    let claimed = generator_staking::claim_unbonded(_deps.storage, &_env, &staking, &_info.sender)?;
+    let amount = claimed.amount;
+
+    Ok(Response::new()
+        .add_message(BankMsg::Send {
+            to_address: _info.sender.to_string(),
+            amount: vec![claimed],
+        })
+        .add_attribute("action", "claim unbonded")
+        .add_attribute("amount", amount))
+}
+
+
+fn handle_register(_deps: DepsMut, _info: MessageInfo, public_key: String) -> Result<Response, ContractError>{
+
+    if GENERATORS.has(_deps.storage, _info.sender.clone()) {
+        return Err(ContractError::AlreadyRegistered{});
     }
 
-    #[test]
-    fn user_request_randomness_success() {
-        let mut deps = setup();
+    // keys are kept in one encoding, so a key cannot be registered twice by spelling it differently
+    let public_key = hex::encode(ecvrf::decode_public_key(&public_key)?.compress().as_bytes());
+    if PUBLIC_KEYS.has(_deps.storage, &public_key) {
+        return Err(ContractError::PublicKeyInUse{});
+    }
 
-        let key_hash = "aabb".to_string();
-        let time_set = 100;
+    let staking = CONFIG.load(_deps.storage)?.staking;
+    let stake = bonded_stake(&staking, &_info.funds)?;
 
-        let request_randomness_msg = ExecuteMsg::RequestRandomness {
-            key_hash: key_hash.clone(), 
-            time_set: time_set,
-        };
+    PUBLIC_KEYS.save(_deps.storage, &public_key, &_info.sender)?;
+    GENERATORS.save(_deps.storage, _info.sender.clone(), &Generator{
+        addr: _info.sender.clone(),
+        public_key,
+        stake,
+    })?;
 
-        let raw_push = execute(deps.as_mut(), mock_env(), mock_info(USER, &[]), request_randomness_msg).unwrap();
-        assert_eq!(raw_push, Response::new().add_attribute("action", "request randomness".to_string()));
+    Ok(Response::new()
+        .add_attribute("action", "register".to_string())
+    )
+}
 
-        let request = RANDOMNESS_REQUEST_STATE.back(&deps.storage).unwrap().unwrap();
+/// Fields of a `RequestRandomness` message
+struct RequestParams {
+    user_seed: Binary,
+    callback_data: Binary,
+    time_set: u128,
+    num_words: u32,
+    mode: RandomnessMode,
+    delivery: Delivery,
+    expires: Option<Expiry>,
+    callback_contract: Option<String>,
+    callback_gas_limit: Option<u64>,
+}
 
-        assert_eq!(request.key_hash, key_hash);
-        assert_eq!(request.time, time_set);
-        assert_eq!(request.user.as_ref(), USER);
+fn handle_request_randomness(_deps: DepsMut, _env: Env, _info: MessageInfo, params: RequestParams) -> Result<Response, ContractError> {
+    let RequestParams{user_seed, callback_data, time_set, num_words, mode, delivery, expires, callback_contract, callback_gas_limit} = params;
+    let config = CONFIG.load(_deps.storage)?;
+
+    if num_words == 0 || num_words > config.max_num_words {
+        return Err(ContractError::InvalidNumWords{max: config.max_num_words});
+    }
+
+    let callback_gas_limit = callback_gas_limit.unwrap_or(config.max_callback_gas_limit);
+    if callback_gas_limit == 0 || callback_gas_limit > config.max_callback_gas_limit {
+        return Err(ContractError::InvalidCallbackGasLimit{max: config.max_callback_gas_limit});
+    }
+
+    let callback_contract = match callback_contract {
+        Some(addr) => _deps.api.addr_validate(&addr)?,
+        None => _info.sender.clone(),
+    };
+    let escrow = escrow_request_fee(&config.request_fee, &_info.funds)?;
+    let drand = config.drand;
+
+    let time_set_seconds = u64::try_from(time_set)
+        .map_err(|_| ContractError::InvalidTimeSet{time_set})?;
+
+    if mode == RandomnessMode::Threshold && !COMMITTEE.exists(_deps.storage) {
+        return Err(ContractError::NoCommittee{});
+    }
+
+    // commit-reveal requests are open to every registered generator, threshold ones to the committee
+    let (assigned_generator, commit_reveal) = match mode {
+        RandomnessMode::Vrf => (Some(assign_generator(_deps.storage)?), None),
+        RandomnessMode::Threshold => (None, None),
+        RandomnessMode::CommitReveal => {
+            let commit_deadline = _env.block.time.plus_seconds(config.commit_reveal.commit_period);
+            (None, Some(CommitRevealWindow {
+                commit_deadline,
+                reveal_deadline: commit_deadline.plus_seconds(config.commit_reveal.reveal_period),
+            }))
+        },
+    };
+
+    // only beacons emitted after the request and not before `time_set` may fulfill it,
+    // with commit-reveal they must also be unknown until all secrets are revealed
+    let not_before = commit_reveal.as_ref()
+        .map(|window| window.reveal_deadline)
+        .unwrap_or(_env.block.time);
+    let min_round = round_after(drand.genesis_time, drand.period, time_set_seconds)
+        .max(round_after(drand.genesis_time, drand.period, not_before.seconds() + 1));
+
+    // the request has to stay open until its round is due, the default timeout runs from then
+    let due = Timestamp::from_seconds(round_time(drand.genesis_time, drand.period, min_round));
+    let expires = expires.or(config.request_timeout.map(|timeout| Expiry::AtTime(due.plus_seconds(timeout))));
+    let expires_too_early = match &expires {
+        Some(Expiry::AtTime(time)) => *time <= due,
+        Some(expires) => expires.is_expired(&_env.block),
+        None => false,
+    };
+    if expires_too_early {
+        return Err(ContractError::InvalidExpiry{});
+    }
+
+    let id = REQUEST_COUNT.update(_deps.storage, |count| -> StdResult<u64> { Ok(count + 1) })?;
+
+    let request = RandomnessRequest {
+        id,
+        user: _info.sender,
+        user_seed,
+        callback_data,
+        callback_contract,
+        callback_gas_limit,
+        time: time_set,
+        num_words,
+        min_round,
+        assigned_generator,
+        status: RequestStatus::Pending,
+        callback_error: None,
+        escrow,
+        mode,
+        delivery,
+        expires,
+        commit_reveal,
+    };
+
+    RANDOMNESS_REQUESTS.save(_deps.storage, id, &request)?;
+
+    match &request.expires {
+        Some(Expiry::AtHeight(height)) => EXPIRIES_BY_HEIGHT.save(_deps.storage, (*height, id), &Empty{})?,
+        Some(Expiry::AtTime(time)) => EXPIRIES_BY_TIME.save(_deps.storage, (time.nanos(), id), &Empty{})?,
+        None => {},
+    }
+
+    Ok(Response::new()
+        .set_data(to_json_binary(&RequestRandomnessResponse{request_id: id})?)
+        .add_attribute("action","request randomness")
+        .add_attribute("request_id", id.to_string()))
+}
+
+/// Picks the generator that must fulfill a new request: the registered generator
+/// after the last assigned one, wrapping around to the first.
+///
+/// Fixing the generator up front keeps any other registered key from proving the
+/// request, so no one can choose among several VRF outputs.
+fn assign_generator(storage: &mut dyn Storage) -> Result<Addr, ContractError> {
+    let next = match LAST_ASSIGNED_GENERATOR.may_load(storage)? {
+        Some(last) => GENERATORS
+            .keys(storage, Some(Bound::exclusive(last)), None, Order::Ascending)
+            .next()
+            .transpose()?,
+        None => None,
+    };
+
+    let generator = match next {
+        Some(generator) => generator,
+        None => GENERATORS
+            .keys(storage, None, None, Order::Ascending)
+            .next()
+            .transpose()?
+            .ok_or(ContractError::NoGenerator{})?,
+    };
+
+    LAST_ASSIGNED_GENERATOR.save(storage, &generator)?;
+    Ok(generator)
+}
+
+/// Loads the request a push of `generator` for drand `round` should fulfill: the given
+/// one if `request_id` is set, otherwise the oldest pending request assigned to
+/// `generator`, scheduled for `round` and not expired.
+fn load_request_to_fulfill(storage: &dyn Storage, block: &BlockInfo, generator: &Addr, request_id: Option<u64>, round: u64) -> Result<Option<RandomnessRequest>, ContractError> {
+    match request_id {
+        Some(id) => {
+            let request = RANDOMNESS_REQUESTS.may_load(storage, id)?
+                .ok_or(ContractError::RequestNotFound{id})?;
+
+            ensure_fulfillable(&request, block)?;
+
+            if request.mode != RandomnessMode::Vrf {
+                return Err(ContractError::WrongRandomnessMode{id});
+            }
+
+            if request.assigned_generator.as_ref() != Some(generator) {
+                return Err(ContractError::GeneratorNotAssigned{id});
+            }
+
+            check_fulfill_round(&request, round)?;
+
+            Ok(Some(request))
+        },
+        None => {
+            let oldest = RANDOMNESS_REQUESTS.idx.round
+                .prefix((RequestStatus::Pending.key(), generator.to_string(), round))
+                .range(storage, None, None, Order::Ascending)
+                .find(|item| match item {
+                    Ok((_, request)) => !request.expires.as_ref().is_some_and(|expires| expires.is_expired(block)),
+                    Err(_) => true,
+                })
+                .transpose()?;
+
+            Ok(oldest.map(|(_, request)| request))
+        },
+    }
+}
+
+/// Checks that drand `round` is the one `request` has to be fulfilled with
+fn check_fulfill_round(request: &RandomnessRequest, round: u64) -> Result<(), ContractError> {
+    if round < request.min_round {
+        return Err(ContractError::DrandRoundTooEarly{round, min_round: request.min_round});
+    }
+
+    // a generator may not pick among several rounds the one that suits it best
+    if round > request.min_round {
+        return Err(ContractError::DrandRoundTooLate{round, min_round: request.min_round});
+    }
+
+    Ok(())
+}
+
+fn decode_hex(field: &str, value: &str) -> Result<Vec<u8>, ContractError> {
+    hex::decode(value).map_err(|_| ContractError::InvalidHex{field: field.to_string()})
+}
+
+// domain separation tags of the generator's VRF input and of the returned randomness
+const PREIMAGE_DOMAIN: &[u8] = b"vrf-wait:preimage:v1";
+const OUTPUT_DOMAIN: &[u8] = b"vrf-wait:output:v1";
+const WORDS_DOMAIN: &[u8] = b"vrf-wait:words:v1";
+
+fn extend_length_prefixed(buf: &mut Vec<u8>, value: &[u8]) {
+    buf.extend((value.len() as u64).to_be_bytes());
+    buf.extend(value);
+}
+
+/// Input of a generator's VRF proof, binding it to this contract, chain, request and drand round:
+/// domain || len || contract address || len || chain id || request id || round || drand randomness,
+/// with lengths and numbers as 8 byte big endian integers.
+fn randomness_preimage(env: &Env, request_id: u64, round: u64, drand_randomness: &[u8]) -> Vec<u8> {
+    let mut preimage = PREIMAGE_DOMAIN.to_vec();
+    extend_length_prefixed(&mut preimage, env.contract.address.as_bytes());
+    extend_length_prefixed(&mut preimage, env.block.chain_id.as_bytes());
+    preimage.extend(request_id.to_be_bytes());
+    preimage.extend(round.to_be_bytes());
+    extend_length_prefixed(&mut preimage, drand_randomness);
+    preimage
+}
+
+/// Randomness returned for a request: sha256(domain || len || VRF output || len || user seed)
+fn derive_output(vrf_output: &[u8], user_seed: &[u8]) -> Vec<u8> {
+    let mut seed = OUTPUT_DOMAIN.to_vec();
+    extend_length_prefixed(&mut seed, vrf_output);
+    extend_length_prefixed(&mut seed, user_seed);
+    sha256_hash(&seed)
+}
+
+/// Expands a request's randomness into `num_words` words: word i is
+/// sha256(domain || len || seed || i), with i as 4 byte big endian integer.
+fn expand_words(seed: &[u8], num_words: u32) -> Vec<Vec<u8>> {
+    let mut prefix = WORDS_DOMAIN.to_vec();
+    extend_length_prefixed(&mut prefix, seed);
+
+    (0..num_words)
+        .map(|counter| {
+            let mut input = prefix.clone();
+            input.extend(counter.to_be_bytes());
+            sha256_hash(&input)
+        })
+        .collect()
+}
+
+fn handle_push_randomness(_deps: DepsMut, _env: Env, _info: MessageInfo, proof: String,
+    drand_response: DrandResponse, request_id: Option<u64>) -> Result<Response, ContractError>{
+    
+    if !GENERATORS.has(_deps.storage, _info.sender.clone()){
+        return Err(ContractError::GeneratorNotRegistered{});
     }
-}
\ No newline at end of file
+
+    let generator = GENERATORS.load(_deps.storage, _info.sender.clone())?;
+
+    let drand = CONFIG.load(_deps.storage)?.drand;
+
+    verify_drand_randomness(
+        &drand.scheme,
+        &drand.public_key,
+        drand_response.round,
+        &drand_response.signature,
+        drand_response.previous_signature.as_deref(),
+    )?;
+
+    let proof_bytes = decode_hex("proof", &proof)?;
+    if let Some(request_id) = USED_PROOFS.may_load(_deps.storage, &proof_bytes)? {
+        return Err(ContractError::ReplayedPush{request_id});
+    }
+    if let Some(request_id) = request_id {
+        if CONSUMED_ROUNDS.has(_deps.storage, (drand_response.round, request_id)) {
+            return Err(ContractError::ReplayedPush{request_id});
+        }
+    }
+
+    let request = load_request_to_fulfill(_deps.storage, &_env.block, &_info.sender, request_id, drand_response.round)?
+        .ok_or(ContractError::NoRequestToFulfill{round: drand_response.round})?;
+
+    let drand_randomness = derive_randomness_from_signature(&decode_hex("signature", &drand_response.signature)?);
+
+    // the VRF output is unique for the key and input, so the generator cannot choose it
+    let preimage = randomness_preimage(&_env, request.id, drand_response.round, &drand_randomness);
+    let vrf_output = match ecvrf::verify(&generator.public_key, &preimage, &proof) {
+        Ok(vrf_output) => vrf_output,
+        // only a push naming its request proves misbehaviour, a queued push may lose a race
+        Err(ecvrf::VrfError::VerificationFailed{}) if request_id.is_some() => {
+            let config = CONFIG.load(_deps.storage)?;
+            let slashed = slash_generator(_deps.storage, &_env, &config.staking, generator, false)?;
+
+            return Ok(Response::new()
+                .add_messages(slash_payouts(&config, slashed, None))
+                .add_attribute("action", "slash generator")
+                .add_attribute("generator", _info.sender)
+                .add_attribute("reason", "invalid proof")
+                .add_attribute("request_id", request.id.to_string())
+                .add_attribute("slashed", slashed));
+        },
+        Err(err) => return Err(err.into()),
+    };
+
+    let return_randomness = derive_output(&vrf_output, &request.user_seed);
+
+    USED_PROOFS.save(_deps.storage, &proof_bytes, &request.id)?;
+
+    // the generator is paid for the push even if the consumer fails to handle the randomness
+    let payment = release_escrow(&request, _info.sender.as_str());
+
+    let request_id = request.id;
+    let callback = fulfill_request(_deps.storage, request, &return_randomness, drand_response.round, Some(&_info.sender), proof)?;
+
+    Ok(Response::new()
+        .add_messages(payment)
+        .add_attribute("action","push randomness".to_string())
+        .add_submessages(callback)
+        .add_attribute("request_id", request_id.to_string()))
+}
+
+/// Marks a request fulfilled with `randomness` and returns the callback delivering it,
+/// if the request is delivered by callback
+fn fulfill_request(storage: &mut dyn Storage, mut request: RandomnessRequest, randomness: &[u8], round: u64,
+    generator: Option<&Addr>, proof: String) -> Result<Option<SubMsg>, ContractError> {
+    let words: Vec<String> = expand_words(randomness, request.num_words).iter().map(hex::encode).collect();
+
+    let callback = match request.delivery {
+        Delivery::Callback => {
+            let return_randomness_msg  = CallBackMsg::ReturnRandomness{
+                randomness: words.clone(),
+                callback_data: request.callback_data.clone(),
+            };
+
+            // the request id doubles as reply id, so a failing consumer, also one running
+            // out of its gas limit, only marks its own request as failed instead of reverting the push
+            let callback = WasmMsg::Execute {
+                contract_addr: request.callback_contract.to_string(),
+                msg: to_json_binary(&return_randomness_msg)?,
+                funds: vec![],
+            };
+            Some(SubMsg::reply_on_error(callback, request.id).with_gas_limit(request.callback_gas_limit))
+        },
+        Delivery::Store => None,
+    };
+
+    request.status = RequestStatus::Fulfilled;
+    RANDOMNESS_REQUESTS.save(storage, request.id, &request)?;
+    if let Some(generator) = generator {
+        CONSUMED_ROUNDS.save(storage, (round, request.id), generator)?;
+    }
+    FULFILLED_RESULTS.save(storage, request.id, &FulfilledResult{
+        request_id: request.id,
+        randomness: hex::encode(randomness),
+        words,
+        round,
+        generator: generator.cloned(),
+        proof,
+    })?;
+
+    Ok(callback)
+}
+
+fn validate_commit_reveal_config(commit_reveal: &CommitRevealConfig) -> Result<(), ContractError> {
+    if commit_reveal.commit_period == 0 {
+        return Err(ContractError::MustNotBeZero{field: "commit_period".to_string()});
+    }
+
+    if commit_reveal.reveal_period == 0 {
+        return Err(ContractError::MustNotBeZero{field: "reveal_period".to_string()});
+    }
+
+    if commit_reveal.min_reveals == 0 {
+        return Err(ContractError::MustNotBeZero{field: "min_reveals".to_string()});
+    }
+
+    if commit_reveal.max_committers < commit_reveal.min_reveals {
+        return Err(ContractError::InvalidMaxCommitters{});
+    }
+
+    Ok(())
+}
+
+fn handle_update_commit_reveal_config(_deps: DepsMut, _info: MessageInfo, commit_reveal: CommitRevealConfig) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(_deps.storage)?;
+
+    if _info.sender != config.owner {
+        return Err(ContractError::Unauthorized{});
+    }
+
+    validate_commit_reveal_config(&commit_reveal)?;
+
+    config.commit_reveal = commit_reveal;
+    CONFIG.save(_deps.storage, &config)?;
+
+    Ok(Response::new().add_attribute("action", "update commit reveal config"))
+}
+
+/// Loads a pending commit-reveal request and its deadlines
+fn load_commit_reveal_request(storage: &dyn Storage, block: &BlockInfo, id: u64) -> Result<(RandomnessRequest, CommitRevealWindow), ContractError> {
+    let request = RANDOMNESS_REQUESTS.may_load(storage, id)?
+        .ok_or(ContractError::RequestNotFound{id})?;
+
+    ensure_fulfillable(&request, block)?;
+
+    let window = request.commit_reveal.clone()
+        .ok_or(ContractError::WrongRandomnessMode{id})?;
+
+    Ok((request, window))
+}
+
+fn handle_commit(_deps: DepsMut, _env: Env, _info: MessageInfo, request_id: u64, commitment: String) -> Result<Response, ContractError> {
+    if !GENERATORS.has(_deps.storage, _info.sender.clone()) {
+        return Err(ContractError::GeneratorNotRegistered{});
+    }
+
+    let (_, window) = load_commit_reveal_request(_deps.storage, &_env.block, request_id)?;
+    if _env.block.time >= window.commit_deadline {
+        return Err(ContractError::WrongCommitRevealPhase{id: request_id, phase: "commit".to_string()});
+    }
+
+    let max = CONFIG.load(_deps.storage)?.commit_reveal.max_committers;
+
+    let commitment_bytes = decode_hex("commitment", &commitment)?;
+    if commitment_bytes.len() != 32 {
+        return Err(ContractError::InvalidLength{field: "commitment".to_string(), expected: 32, actual: commitment_bytes.len()});
+    }
+
+    let key = (request_id, _info.sender.clone());
+    if COMMITMENTS.has(_deps.storage, key.clone()) {
+        return Err(ContractError::AlreadyCommitted{id: request_id});
+    }
+
+    // finalizing walks every commitment, so their number is capped
+    let committers = COMMITMENTS.prefix(request_id)
+        .keys_raw(_deps.storage, None, None, Order::Ascending)
+        .take(max as usize)
+        .count();
+    if committers >= max as usize {
+        return Err(ContractError::TooManyCommitters{id: request_id, max});
+    }
+
+    COMMITMENTS.save(_deps.storage, key, &Commitment {
+        generator: _info.sender.clone(),
+        commitment: hex::encode(commitment_bytes),
+        secret: None,
+    })?;
+
+    Ok(Response::new()
+        .add_attribute("action", "commit")
+        .add_attribute("request_id", request_id.to_string())
+        .add_attribute("generator", _info.sender))
+}
+
+fn handle_reveal(_deps: DepsMut, _env: Env, _info: MessageInfo, request_id: u64, secret: String) -> Result<Response, ContractError> {
+    let (_, window) = load_commit_reveal_request(_deps.storage, &_env.block, request_id)?;
+    if _env.block.time < window.commit_deadline || _env.block.time >= window.reveal_deadline {
+        return Err(ContractError::WrongCommitRevealPhase{id: request_id, phase: "reveal".to_string()});
+    }
+
+    let key = (request_id, _info.sender.clone());
+    let mut commitment = COMMITMENTS.may_load(_deps.storage, key.clone())?
+        .ok_or(ContractError::NoCommitment{id: request_id})?;
+
+    let secret_bytes = decode_hex("secret", &secret)?;
+    if hex::encode(sha256_hash(&secret_bytes)) != commitment.commitment {
+        return Err(ContractError::CommitmentMismatch{id: request_id});
+    }
+
+    commitment.secret = Some(hex::encode(secret_bytes));
+    COMMITMENTS.save(_deps.storage, key, &commitment)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "reveal")
+        .add_attribute("request_id", request_id.to_string())
+        .add_attribute("generator", _info.sender))
+}
+
+/// Slashes a generator that withheld its reveal, also if it already left and its stake is unbonding
+fn slash_withheld_reveal(storage: &mut dyn Storage, env: &Env, staking: &StakingConfig, addr: &Addr) -> StdResult<Uint128> {
+    if let Some(generator) = GENERATORS.may_load(storage, addr.clone())? {
+        return slash_generator(storage, env, staking, generator, false);
+    }
+
+    match UNBONDINGS.may_load(storage, addr.clone())? {
+        Some(mut unbonding) => {
+            let slashed = unbonding.amount.mul_floor(staking.slash_fraction);
+            unbonding.amount -= slashed;
+            UNBONDINGS.save(storage, addr.clone(), &unbonding)?;
+            Ok(slashed)
+        },
+        None => Ok(Uint128::zero()),
+    }
+}
+
+/// Splits the escrow of a request evenly, the first recipient also gets what cannot be split
+fn split_escrow(request: &RandomnessRequest, recipients: &[Addr]) -> Vec<BankMsg> {
+    let shares = Uint128::from(recipients.len() as u128);
+
+    recipients.iter().enumerate()
+        .filter_map(|(i, recipient)| {
+            let amount: Vec<Coin> = request.escrow.iter()
+                .map(|fee| {
+                    let share = fee.amount / shares;
+                    let rest = if i == 0 { fee.amount - share * shares } else { Uint128::zero() };
+                    Coin{denom: fee.denom.clone(), amount: share + rest}
+                })
+                .filter(|coin| !coin.amount.is_zero())
+                .collect();
+
+            (!amount.is_empty()).then(|| BankMsg::Send{to_address: recipient.to_string(), amount})
+        })
+        .collect()
+}
+
+const COMMIT_REVEAL_DOMAIN: &[u8] = b"vrf-wait:commit-reveal:v1";
+const THRESHOLD_DOMAIN: &[u8] = b"vrf-wait:threshold:v1";
+
+fn handle_finalize_commit_reveal(_deps: DepsMut, _env: Env, _info: MessageInfo, request_id: u64,
+    drand_response: DrandResponse) -> Result<Response, ContractError> {
+    let (request, window) = load_commit_reveal_request(_deps.storage, &_env.block, request_id)?;
+    if _env.block.time < window.reveal_deadline {
+        return Err(ContractError::WrongCommitRevealPhase{id: request_id, phase: "finalize".to_string()});
+    }
+
+    let config = CONFIG.load(_deps.storage)?;
+
+    let commitments = COMMITMENTS
+        .prefix(request_id)
+        .range(_deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, commitment)| commitment))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let mut withheld = Uint128::zero();
+    for commitment in commitments.iter().filter(|commitment| commitment.secret.is_none()) {
+        withheld += slash_withheld_reveal(_deps.storage, &_env, &config.staking, &commitment.generator)?;
+    }
+
+    let revealed: Vec<&Commitment> = commitments.iter()
+        .filter(|commitment| commitment.secret.is_some())
+        .collect();
+
+    // too few secrets could be chosen by too few generators
+    if revealed.len() < config.commit_reveal.min_reveals as usize {
+        let mut request = request;
+        request.status = RequestStatus::Cancelled;
+        RANDOMNESS_REQUESTS.save(_deps.storage, request_id, &request)?;
+
+        return Ok(Response::new()
+            .add_messages(slash_payouts(&config, withheld, None))
+            .add_messages(release_escrow(&request, request.user.as_str()))
+            .add_attribute("action", "cancel commit reveal")
+            .add_attribute("request_id", request_id.to_string())
+            .add_attribute("reveals", revealed.len().to_string()));
+    }
+
+    verify_drand_randomness(
+        &config.drand.scheme,
+        &config.drand.public_key,
+        drand_response.round,
+        &drand_response.signature,
+        drand_response.previous_signature.as_deref(),
+    )?;
+    check_fulfill_round(&request, drand_response.round)?;
+
+    let drand_randomness = derive_randomness_from_signature(&decode_hex("signature", &drand_response.signature)?);
+
+    // sha256(domain || preimage || len || generator || len || secret ...) over the reveals
+    // ordered by generator, the drand beacon was unknown while anyone could still withhold
+    let mut seed = COMMIT_REVEAL_DOMAIN.to_vec();
+    seed.extend(randomness_preimage(&_env, request_id, drand_response.round, &drand_randomness));
+    for commitment in revealed.iter() {
+        extend_length_prefixed(&mut seed, commitment.generator.as_bytes());
+        extend_length_prefixed(&mut seed, &decode_hex("secret", commitment.secret.as_deref().unwrap_or_default())?);
+    }
+
+    let return_randomness = derive_output(&sha256_hash(&seed), &request.user_seed);
+
+    let revealers: Vec<Addr> = revealed.iter().map(|commitment| commitment.generator.clone()).collect();
+    let payment = split_escrow(&request, &revealers);
+
+    let callback = fulfill_request(_deps.storage, request, &return_randomness, drand_response.round, None, String::new())?;
+
+    Ok(Response::new()
+        .add_messages(payment)
+        .add_messages(slash_payouts(&config, withheld, None))
+        .add_attribute("action", "finalize commit reveal")
+        .add_submessages(callback)
+        .add_attribute("request_id", request_id.to_string())
+        .add_attribute("reveals", revealers.len().to_string()))
+}
+
+fn handle_register_committee(_deps: DepsMut, _info: MessageInfo, public_key: String, members: Vec<String>,
+    threshold: u32) -> Result<Response, ContractError> {
+    let config = CONFIG.load(_deps.storage)?;
+
+    if _info.sender != config.owner {
+        return Err(ContractError::Unauthorized{});
+    }
+
+    decode_g1_public_key(&public_key)?;
+
+    if threshold == 0 || threshold as usize > members.len() {
+        return Err(ContractError::InvalidCommitteeThreshold{members: members.len()});
+    }
+
+    let members = members.iter()
+        .map(|member| _deps.api.addr_validate(member))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    COMMITTEE.save(_deps.storage, &Committee{public_key, members, threshold})?;
+
+    Ok(Response::new()
+        .add_attribute("action", "register committee")
+        .add_attribute("threshold", threshold.to_string()))
+}
+
+/// Message signed by the committee for a threshold request:
+/// domain || len || contract address || len || chain id || request id || min round
+fn threshold_message(env: &Env, request_id: u64, min_round: u64) -> Vec<u8> {
+    let mut message = THRESHOLD_DOMAIN.to_vec();
+    extend_length_prefixed(&mut message, env.contract.address.as_bytes());
+    extend_length_prefixed(&mut message, env.block.chain_id.as_bytes());
+    message.extend(request_id.to_be_bytes());
+    message.extend(min_round.to_be_bytes());
+    message
+}
+
+fn handle_push_threshold_signature(_deps: DepsMut, _env: Env, _info: MessageInfo, request_id: u64,
+    signature: String) -> Result<Response, ContractError> {
+    let request = RANDOMNESS_REQUESTS.may_load(_deps.storage, request_id)?
+        .ok_or(ContractError::RequestNotFound{id: request_id})?;
+
+    ensure_fulfillable(&request, &_env.block)?;
+
+    if request.mode != RandomnessMode::Threshold {
+        return Err(ContractError::WrongRandomnessMode{id: request_id});
+    }
+
+    let drand = CONFIG.load(_deps.storage)?.drand;
+    let due = round_time(drand.genesis_time, drand.period, request.min_round);
+    if _env.block.time.seconds() < due {
+        return Err(ContractError::RequestNotDue{id: request_id, time: due});
+    }
+
+    let committee = COMMITTEE.load(_deps.storage)?;
+
+    // BLS signatures are unique, below the threshold nobody can compute or influence it
+    verify_bls_signature(&committee.public_key, &threshold_message(&_env, request_id, request.min_round), &signature)
+        .map_err(|err| match err {
+            DrandError::VerificationFailed{} => ContractError::InvalidGroupSignature{},
+            err => err.into(),
+        })?;
+
+    let signature_bytes = decode_hex("signature", &signature)?;
+    let return_randomness = derive_output(&signature_bytes, &request.user_seed);
+
+    let payment = split_escrow(&request, &committee.members);
+    let round = request.min_round;
+    let callback = fulfill_request(_deps.storage, request, &return_randomness, round, None, hex::encode(signature_bytes))?;
+
+    Ok(Response::new()
+        .add_messages(payment)
+        .add_attribute("action", "push threshold signature")
+        .add_submessages(callback)
+        .add_attribute("request_id", request_id.to_string()))
+}
+
+/// Handling contract query
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(_deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        // Find matched incoming message variant and query them your custom logic
+        // and then construct your query response with the type usually defined
+        // `msg.rs` alongside with the query message itself.
+        //
+        // use `cosmwasm_std::to_json_binary` to serialize query response to json binary.
+        QueryMsg::Config{} => to_json_binary(&query_config(_deps)?),
+        QueryMsg::Generator{addr} => to_json_binary(&query_generator(_deps, addr)?),
+        QueryMsg::ListGenerators{start_after, limit} => to_json_binary(&query_list_generators(_deps, start_after, limit)?),
+        QueryMsg::Unbonding{addr} => to_json_binary(&query_unbonding(_deps, addr)?),
+        QueryMsg::Request{id} => to_json_binary(&query_request(_deps, id)?),
+        QueryMsg::PendingRequests{start_after, limit} => to_json_binary(&query_pending_requests(_deps, start_after, limit)?),
+        QueryMsg::RequestsByUser{user, start_after, limit} => to_json_binary(&query_requests_by_user(_deps, user, start_after, limit)?),
+        QueryMsg::FulfilledResult{id} => to_json_binary(&query_fulfilled_result(_deps, id)?),
+        QueryMsg::RandomnessResult{id} => to_json_binary(&query_randomness_result(_deps, id)?),
+        QueryMsg::Offence{generator, request_id} => to_json_binary(&query_offence(_deps, generator, request_id)?),
+        QueryMsg::Commitments{request_id} => to_json_binary(&query_commitments(_deps, request_id)?),
+        QueryMsg::Committee{} => to_json_binary(&query_committee(_deps)?),
+    }
+}
+
+fn query_config(_deps: Deps) -> StdResult<Config> {
+    CONFIG.load(_deps.storage)
+}
+
+fn query_committee(_deps: Deps) -> StdResult<Committee> {
+    COMMITTEE.load(_deps.storage)
+}
+
+fn query_generator(_deps: Deps, addr: String) -> StdResult<Generator> {
+    let addr = _deps.api.addr_validate(&addr)?;
+    GENERATORS.load(_deps.storage, addr)
+}
+
+fn query_unbonding(_deps: Deps, addr: String) -> StdResult<Unbonding> {
+    let addr = _deps.api.addr_validate(&addr)?;
+    UNBONDINGS.load(_deps.storage, addr)
+}
+
+fn query_list_generators(_deps: Deps, start_after: Option<String>, limit: Option<u32>) -> StdResult<GeneratorsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after
+        .map(|addr| _deps.api.addr_validate(&addr))
+        .transpose()?
+        .map(Bound::exclusive);
+
+    let generators = GENERATORS
+        .range(_deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, generator)| generator))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(GeneratorsResponse{generators})
+}
+
+fn query_request(_deps: Deps, id: u64) -> StdResult<RandomnessRequest> {
+    RANDOMNESS_REQUESTS.load(_deps.storage, id)
+}
+
+fn query_pending_requests(_deps: Deps, start_after: Option<u64>, limit: Option<u32>) -> StdResult<RequestsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let requests = RANDOMNESS_REQUESTS.idx.status
+        .prefix(RequestStatus::Pending.key())
+        .range(_deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, request)| request))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(RequestsResponse{requests})
+}
+
+fn query_requests_by_user(_deps: Deps, user: String, start_after: Option<u64>, limit: Option<u32>) -> StdResult<RequestsResponse> {
+    let user = _deps.api.addr_validate(&user)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let requests = RANDOMNESS_REQUESTS.idx.user
+        .prefix(user)
+        .range(_deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, request)| request))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(RequestsResponse{requests})
+}
+
+fn query_commitments(_deps: Deps, request_id: u64) -> StdResult<CommitmentsResponse> {
+    let commitments = COMMITMENTS
+        .prefix(request_id)
+        .range(_deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, commitment)| commitment))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(CommitmentsResponse{commitments})
+}
+
+fn query_offence(_deps: Deps, generator: String, request_id: u64) -> StdResult<Offence> {
+    let generator = _deps.api.addr_validate(&generator)?;
+    OFFENCES.load(_deps.storage, (generator, request_id))
+}
+
+fn query_fulfilled_result(_deps: Deps, id: u64) -> StdResult<FulfilledResult> {
+    FULFILLED_RESULTS.load(_deps.storage, id)
+}
+
+fn query_randomness_result(_deps: Deps, id: u64) -> StdResult<RandomnessResultResponse> {
+    let result = FULFILLED_RESULTS.load(_deps.storage, id)?;
+    Ok(RandomnessResultResponse{
+        request_id: result.request_id,
+        randomness: result.words,
+        round: result.round,
+        proof: result.proof,
+    })
+}
+
+/// Handling submessage reply.
+/// For more info on submessage and reply, see https://github.com/CosmWasm/cosmwasm/blob/main/SEMANTICS.md#submessages
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(_deps: DepsMut, _env: Env, _msg: Reply) -> Result<Response, ContractError> {
+    // With `Response` type, it is still possible to dispatch message to invoke external logic.
+    // See: https://github.com/CosmWasm/cosmwasm/blob/main/SEMANTICS.md#dispatching-messages
+    let mut request = RANDOMNESS_REQUESTS.may_load(_deps.storage, _msg.id)?
+        .ok_or(ContractError::RequestNotFound{id: _msg.id})?;
+
+    match _msg.result {
+        SubMsgResult::Ok(_) => Ok(Response::new()
+            .add_attribute("action", "callback delivered")
+            .add_attribute("request_id", request.id.to_string())),
+        SubMsgResult::Err(err) => {
+            request.status = RequestStatus::Failed;
+            request.callback_error = Some(err.clone());
+            RANDOMNESS_REQUESTS.save(_deps.storage, request.id, &request)?;
+
+            Ok(Response::new()
+                .add_attribute("action", "callback failed")
+                .add_attribute("request_id", request.id.to_string())
+                .add_attribute("error", err))
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{
+        mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
+    };
+    use crate::state::DrandScheme;
+    use cosmwasm_std::{coin, coins, from_json, Addr, CosmosMsg, Decimal, OwnedDeps, ReplyOn, SubMsgResponse, Timestamp};
+
+    const CREATOR: &str = "creator";
+    const GENERATOR: &str = "generator";
+    const USER: &str = "user";
+    const TREASURY: &str = "treasury";
+    const UNBONDING_PERIOD: u64 = 3 * 24 * 60 * 60;
+    const COMMIT_PERIOD: u64 = 60;
+    const REVEAL_PERIOD: u64 = 60;
+    const MAX_NUM_WORDS: u32 = 10;
+    const MAX_CALLBACK_GAS_LIMIT: u64 = 500_000;
+    const USER_SEED: &[u8] = b"user seed";
+
+    /// Secret key of RFC 9381 example 16, the generator's VRF key in tests
+    const GENERATOR_SECRET_KEY: [u8; 32] = hex_literal::hex!("9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60");
+    const GENERATOR_PUBLIC_KEY: &str = "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a";
+
+    // League of Entropy Mainnet round 2515680 (curl -sS https://drand.cloudflare.com/public/2515680)
+    const ROUND: u64 = 2515680;
+    const ROUND_SIGNATURE: &str = "b46015d219fb64ad572c32ae5104e3d6498ea9a7e8045d5f7b1355f20b3c05894f7c9b7af2a5d4499dc6c8f3a50f85d209b903e2c0660075a994e463c99c49582eab9d991a35fba2784979f51a5943476a3cf2096762be81f2d90a9bc1097259";
+    const ROUND_PREVIOUS_SIGNATURE: &str = "b70285d05e8021c296f4e1fc4f7624210a5540a59628df703bcceebb7f5df9c1ce205956c23a5ec7bc31bc431535b53007d2abb32a2a84f4d1318aba0cfa9088d6a11aac135c56e77b14ed0ee2247084066a1590bec22f1e0802460ce18d21ba";
+    // drand round 2515680 of League of Entropy Mainnet was emitted at this time
+    const ROUND_2515680_TIME: u128 = 1670901420;
+
+    /// League of Entropy Mainnet (curl -sS https://drand.cloudflare.com/info)
+    fn leo_mainnet() -> DrandConfig {
+        DrandConfig {
+            public_key: "868f005eb8e6e4ca0a47c8a77ceaa5309a47978a7c71bc5cce96366b5d7a569937c529eeda66c7293784a9402801af31".to_string(),
+            chain_hash: "8990e7a9aaed2ffed73dbd7092123d6f289930540d7651336225dc172e51b2ce".to_string(),
+            genesis_time: 1595431050,
+            period: 30,
+            scheme: DrandScheme::PedersenBlsChained,
+        }
+    }
+
+    fn staking() -> StakingConfig {
+        StakingConfig {
+            denom: "ustake".to_string(),
+            min_stake: Uint128::new(1000),
+            unbonding_period: UNBONDING_PERIOD,
+            slash_fraction: Decimal::percent(10),
+            treasury: Addr::unchecked(TREASURY),
+        }
+    }
+
+    fn commit_reveal() -> CommitRevealConfig {
+        CommitRevealConfig {
+            commit_period: COMMIT_PERIOD,
+            reveal_period: REVEAL_PERIOD,
+            min_reveals: 2,
+            max_committers: 3,
+        }
+    }
+
+    fn stake() -> Vec<Coin> {
+        coins(1000, "ustake")
+    }
+
+    fn setup() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+        setup_with_fee(None)
+    }
+
+    fn setup_with_fee(request_fee: Option<Coin>) -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            drand: leo_mainnet(),
+            request_fee,
+            staking: staking(),
+            reporter_reward: Decimal::percent(50),
+            commit_reveal: commit_reveal(),
+            request_timeout: None,
+            max_num_words: MAX_NUM_WORDS,
+            max_callback_gas_limit: MAX_CALLBACK_GAS_LIMIT,
+        };
+        let info = mock_info(CREATOR, &[]);
+        let res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(0, res.messages.len());
+        deps
+    }
+
+    #[test]
+    fn instantiate_works() {
+        setup();
+    }
+
+    #[test]
+    fn generator_register_success() {
+        let mut deps = setup();
+
+        let public_key: String = GENERATOR_PUBLIC_KEY.to_string();
+
+        let register_msg = ExecuteMsg::Register{
+            public_key
+        };
+
+        let raw = execute(deps.as_mut(), mock_env(), mock_info(GENERATOR, &stake()), register_msg).unwrap();
+
+        assert_eq!(raw, Response::new().add_attribute("action", "register".to_string()))
+    }
+
+    #[test]
+    fn generator_register_twice_and_delete_unregistered_fail() {
+        let mut deps = setup();
+        register_generator(&mut deps);
+
+        let register_msg = ExecuteMsg::Register{
+            public_key: "03224d723fb48f1c247e105bca7853fb5c711e59fc824f5e581614d80bfbde1c99".to_string(),
+        };
+        match execute(deps.as_mut(), mock_env(), mock_info(GENERATOR, &[]), register_msg).unwrap_err() {
+            ContractError::AlreadyRegistered{} => {},
+            _ => panic!("")
+        }
+
+        match execute(deps.as_mut(), mock_env(), mock_info(USER, &[]), ExecuteMsg::DeleteGenerator{}).unwrap_err() {
+            ContractError::GeneratorNotRegistered{} => {},
+            _ => panic!("")
+        }
+    }
+
+    #[test]
+    fn generator_push_randomness_success() {
+        let mut deps = setup();
+
+        let register_msg = ExecuteMsg::Register{
+            public_key: GENERATOR_PUBLIC_KEY.to_string(),
+        };
+
+        let raw = execute(deps.as_mut(), mock_env(), mock_info(GENERATOR, &stake()), register_msg).unwrap();
+
+        assert_eq!(raw, Response::new().add_attribute("action", "register".to_string()));
+
+        let id = request_randomness(&mut deps, USER);
+
+        let push_randomness_msg = ExecuteMsg::PushRandomness {
+            proof: prove_request(id),
+            drand_response: drand_response(),
+            request_id: None,
+        };
+
+        let raw_push = execute(deps.as_mut(), mock_env(), mock_info(GENERATOR, &[]), push_randomness_msg).unwrap();
+
+        assert_eq!(raw_push.attributes, vec![
+            ("action", "push randomness".to_string()),
+            ("request_id", id.to_string()),
+        ]);
+    }
+
+    #[test]
+    fn generator_push_randomness_fail_with_unregistered_address() {
+        let mut deps = setup();
+        register_generator(&mut deps);
+        let id = request_randomness(&mut deps, USER);
+
+        let push_randomness_msg = ExecuteMsg::PushRandomness {
+            proof: prove_request(id),
+            drand_response: drand_response(),
+            request_id: None,
+        };
+
+        let raw_push = execute(deps.as_mut(), mock_env(), mock_info(CREATOR, &[]), push_randomness_msg).unwrap_err();
+
+        match raw_push {
+            ContractError::GeneratorNotRegistered{} => {},
+            _ => panic!("")
+        }
+    }
+
+    #[test]
+    fn generator_push_randomness_fail_on_other_contract_or_chain() {
+        let mut deps = setup();
+        // enough stake to stay registered after being slashed twice
+        let register_msg = ExecuteMsg::Register{public_key: GENERATOR_PUBLIC_KEY.to_string()};
+        execute(deps.as_mut(), mock_env(), mock_info(GENERATOR, &coins(2000, "ustake")), register_msg).unwrap();
+        let id = request_randomness(&mut deps, USER);
+
+        let mut other_chain = mock_env();
+        other_chain.block.chain_id = "other-chain".to_string();
+        let mut other_contract = mock_env();
+        other_contract.contract.address = Addr::unchecked("other-contract");
+
+        for env in [other_chain, other_contract] {
+            // a queued push is rejected
+            let push_randomness_msg = ExecuteMsg::PushRandomness {
+                proof: prove_request(id),
+                drand_response: drand_response(),
+                request_id: None,
+            };
+            match execute(deps.as_mut(), env.clone(), mock_info(GENERATOR, &[]), push_randomness_msg).unwrap_err() {
+                ContractError::InvalidVrfProof{} => {},
+                _ => panic!("")
+            }
+
+            // a push naming the request is slashed
+            let push_randomness_msg = ExecuteMsg::PushRandomness {
+                proof: prove_request(id),
+                drand_response: drand_response(),
+                request_id: Some(id),
+            };
+            let res = execute(deps.as_mut(), env, mock_info(GENERATOR, &[]), push_randomness_msg).unwrap();
+            assert_eq!(res.attributes[0], ("action", "slash generator"));
+            assert_eq!(res.attributes[2], ("reason", "invalid proof"));
+        }
+
+        let generator: Generator = from_json(query(deps.as_ref(), mock_env(),
+            QueryMsg::Generator{addr: GENERATOR.to_string()}).unwrap()).unwrap();
+        assert_eq!(generator.stake, Uint128::new(1620));
+
+        let push_randomness_msg = ExecuteMsg::PushRandomness {
+            proof: prove_request(id),
+            drand_response: drand_response(),
+            request_id: Some(id),
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info(GENERATOR, &[]), push_randomness_msg).unwrap();
+        assert_eq!(res.attributes[0], ("action", "push randomness"));
+    }
+
+    #[test]
+    fn randomness_preimage_binds_all_fields() {
+        let env = mock_env();
+        let preimage = randomness_preimage(&env, 1, ROUND, &[7u8; 32]);
+
+        let mut expected = b"vrf-wait:preimage:v1".to_vec();
+        expected.extend(15u64.to_be_bytes());
+        expected.extend(b"cosmos2contract");
+        expected.extend(20u64.to_be_bytes());
+        expected.extend(b"cosmos-testnet-14002");
+        expected.extend(1u64.to_be_bytes());
+        expected.extend(ROUND.to_be_bytes());
+        expected.extend(32u64.to_be_bytes());
+        expected.extend([7u8; 32]);
+        assert_eq!(preimage, expected);
+
+        assert_ne!(preimage, randomness_preimage(&env, 2, ROUND, &[7u8; 32]));
+        assert_ne!(preimage, randomness_preimage(&env, 1, ROUND + 1, &[7u8; 32]));
+        assert_ne!(preimage, randomness_preimage(&env, 1, ROUND, &[8u8; 32]));
+    }
+
+    #[test]
+    fn generator_push_randomness_fail_with_invalid_drand_randomness() {
+        let mut deps = setup();
+        register_generator(&mut deps);
+        let id = request_randomness(&mut deps, USER);
+
+        let mut drand_response = drand_response();
+        drand_response.signature = format!("a{}", &ROUND_SIGNATURE[1..]);
+
+        let push_randomness_msg = ExecuteMsg::PushRandomness {
+            proof: prove_request(id),
+            drand_response,
+            request_id: None,
+        };
+
+        let raw_push = execute(deps.as_mut(), mock_env(), mock_info(GENERATOR, &[]), push_randomness_msg).unwrap_err();
+
+        match raw_push {
+            ContractError::InvalidPoint{field, ..} => {assert_eq!(field, "signature".to_string());},
+            _ => panic!("")
+        }
+    }
+
+    #[test]
+    fn generator_push_randomness_fail_with_invalid_proof() {
+        let mut deps = setup();
+        register_generator(&mut deps);
+        let first = request_randomness(&mut deps, USER);
+
+        // a proof by another key
+        let push_randomness_msg = ExecuteMsg::PushRandomness {
+            proof: ecvrf::prover::prove(&[7u8; 32], &request_alpha(first)),
+            drand_response: drand_response(),
+            request_id: None,
+        };
+
+        let raw_push = execute(deps.as_mut(), mock_env(), mock_info(GENERATOR, &[]), push_randomness_msg).unwrap_err();
+
+        match raw_push {
+            ContractError::InvalidVrfProof{} => {},
+            _ => panic!("")
+        }
+
+        // a queued push is not slashed
+        let generator: Generator = from_json(query(deps.as_ref(), mock_env(),
+            QueryMsg::Generator{addr: GENERATOR.to_string()}).unwrap()).unwrap();
+        assert_eq!(generator.stake, Uint128::new(1000));
+    }
+
+    #[test]
+    fn invalid_proof_for_named_request_is_slashed() {
+        let mut deps = setup();
+        register_generator(&mut deps);
+        let first = request_randomness(&mut deps, USER);
+        let second = request_randomness(&mut deps, USER);
+
+        // a proof for another request
+        let push_randomness_msg = ExecuteMsg::PushRandomness {
+            proof: prove_request(second),
+            drand_response: drand_response(),
+            request_id: Some(first),
+        };
+
+        let res = execute(deps.as_mut(), mock_env(), mock_info(GENERATOR, &[]), push_randomness_msg).unwrap();
+        assert_eq!(res.messages, vec![SubMsg::new(BankMsg::Send {
+            to_address: TREASURY.to_string(),
+            amount: coins(100, "ustake"),
+        })]);
+        assert_eq!(res.attributes[0], ("action", "slash generator"));
+        assert_eq!(res.attributes[2], ("reason", "invalid proof"));
+
+        // the request stays pending for an honest push
+        let request: RandomnessRequest = from_json(query(deps.as_ref(), mock_env(), QueryMsg::Request{id: first}).unwrap()).unwrap();
+        assert_eq!(request.status, RequestStatus::Pending);
+
+        // the generator fell below the minimum stake, the rest unbonds
+        query(deps.as_ref(), mock_env(), QueryMsg::Generator{addr: GENERATOR.to_string()}).unwrap_err();
+        let unbonding: Unbonding = from_json(query(deps.as_ref(), mock_env(),
+            QueryMsg::Unbonding{addr: GENERATOR.to_string()}).unwrap()).unwrap();
+        assert_eq!(unbonding.amount, Uint128::new(900));
+        assert_eq!(unbonding.release_at, mock_env().block.time.plus_seconds(UNBONDING_PERIOD));
+    }
+
+    #[test]
+    fn slashed_generator_above_minimum_stays_registered() {
+        let mut deps = setup();
+        let register_msg = ExecuteMsg::Register{public_key: GENERATOR_PUBLIC_KEY.to_string()};
+        execute(deps.as_mut(), mock_env(), mock_info(GENERATOR, &coins(2000, "ustake")), register_msg).unwrap();
+        let id = request_randomness(&mut deps, USER);
+
+        let push_randomness_msg = ExecuteMsg::PushRandomness {
+            proof: ecvrf::prover::prove(&[7u8; 32], &request_alpha(id)),
+            drand_response: drand_response(),
+            request_id: Some(id),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info(GENERATOR, &[]), push_randomness_msg).unwrap();
+
+        let generator: Generator = from_json(query(deps.as_ref(), mock_env(),
+            QueryMsg::Generator{addr: GENERATOR.to_string()}).unwrap()).unwrap();
+        assert_eq!(generator.stake, Uint128::new(1800));
+    }
+
+    fn signed_push(request_id: u64, drand_randomness: &[u8]) -> SignedPush {
+        let preimage = randomness_preimage(&mock_env(), request_id, ROUND, drand_randomness);
+        SignedPush {
+            request_id,
+            round: ROUND,
+            drand_randomness: hex::encode(drand_randomness),
+            proof: ecvrf::prover::prove(&GENERATOR_SECRET_KEY, &preimage),
+        }
+    }
+
+    fn genuine_randomness() -> Vec<u8> {
+        derive_randomness_from_signature(&hex::decode(ROUND_SIGNATURE).unwrap())
+    }
+
+    #[test]
+    fn equivocation_evidence_slashes_and_deregisters() {
+        let mut deps = setup();
+        register_generator(&mut deps);
+        let id = request_randomness(&mut deps, USER);
+
+        let evidence_msg = ExecuteMsg::SubmitEvidence {
+            generator: GENERATOR.to_string(),
+            drand_response: drand_response(),
+            first: signed_push(id, &genuine_randomness()),
+            second: signed_push(id, &[0u8; 32]),
+        };
+
+        let res = execute(deps.as_mut(), mock_env(), mock_info("reporter", &[]), evidence_msg.clone()).unwrap();
+        assert_eq!(res.messages, vec![
+            SubMsg::new(BankMsg::Send {to_address: "reporter".to_string(), amount: coins(50, "ustake")}),
+            SubMsg::new(BankMsg::Send {to_address: TREASURY.to_string(), amount: coins(50, "ustake")}),
+        ]);
+        assert_eq!(res.attributes[2], ("reason", "equivocation"));
+
+        let offence: Offence = from_json(query(deps.as_ref(), mock_env(),
+            QueryMsg::Offence{generator: GENERATOR.to_string(), request_id: id}).unwrap()).unwrap();
+        assert_eq!(offence.reporter.as_ref(), "reporter");
+        assert_eq!(offence.slashed, Uint128::new(100));
+
+        // the generator is out even though 900 of its stake is left, and its key is free again
+        query(deps.as_ref(), mock_env(), QueryMsg::Generator{addr: GENERATOR.to_string()}).unwrap_err();
+        assert!(!PUBLIC_KEYS.has(&deps.storage, GENERATOR_PUBLIC_KEY));
+        let unbonding: Unbonding = from_json(query(deps.as_ref(), mock_env(),
+            QueryMsg::Unbonding{addr: GENERATOR.to_string()}).unwrap()).unwrap();
+        assert_eq!(unbonding.amount, Uint128::new(900));
+
+        // an offence is punished once
+        register_generator(&mut deps);
+        match execute(deps.as_mut(), mock_env(), mock_info("reporter", &[]), evidence_msg).unwrap_err() {
+            ContractError::OffenceAlreadyRecorded{request_id, ..} => assert_eq!(request_id, id),
+            _ => panic!("")
+        }
+    }
+
+    #[test]
+    fn evidence_must_conflict() {
+        let mut deps = setup();
+        register_generator(&mut deps);
+        let id = request_randomness(&mut deps, USER);
+
+        // the same output twice
+        let evidence_msg = ExecuteMsg::SubmitEvidence {
+            generator: GENERATOR.to_string(),
+            drand_response: drand_response(),
+            first: signed_push(id, &genuine_randomness()),
+            second: signed_push(id, &genuine_randomness()),
+        };
+        match execute(deps.as_mut(), mock_env(), mock_info("reporter", &[]), evidence_msg).unwrap_err() {
+            ContractError::EvidenceNotConflicting{} => {},
+            _ => panic!("")
+        }
+
+        // outputs for different requests
+        let evidence_msg = ExecuteMsg::SubmitEvidence {
+            generator: GENERATOR.to_string(),
+            drand_response: drand_response(),
+            first: signed_push(id, &genuine_randomness()),
+            second: signed_push(id + 1, &[0u8; 32]),
+        };
+        match execute(deps.as_mut(), mock_env(), mock_info("reporter", &[]), evidence_msg).unwrap_err() {
+            ContractError::EvidenceNotConflicting{} => {},
+            _ => panic!("")
+        }
+
+        // a proof the generator did not make
+        let mut forged = signed_push(id, &[0u8; 32]);
+        forged.proof = ecvrf::prover::prove(&[7u8; 32], &randomness_preimage(&mock_env(), id, ROUND, &[0u8; 32]));
+        let evidence_msg = ExecuteMsg::SubmitEvidence {
+            generator: GENERATOR.to_string(),
+            drand_response: drand_response(),
+            first: signed_push(id, &genuine_randomness()),
+            second: forged,
+        };
+        match execute(deps.as_mut(), mock_env(), mock_info("reporter", &[]), evidence_msg).unwrap_err() {
+            ContractError::InvalidVrfProof{} => {},
+            _ => panic!("")
+        }
+
+        let generator: Generator = from_json(query(deps.as_ref(), mock_env(),
+            QueryMsg::Generator{addr: GENERATOR.to_string()}).unwrap()).unwrap();
+        assert_eq!(generator.stake, Uint128::new(1000));
+    }
+
+    #[test]
+    fn evidence_must_match_the_drand_beacon() {
+        let mut deps = setup();
+        register_generator(&mut deps);
+        let id = request_randomness(&mut deps, USER);
+
+        // neither proof is over the beacon's randomness
+        let evidence_msg = ExecuteMsg::SubmitEvidence {
+            generator: GENERATOR.to_string(),
+            drand_response: drand_response(),
+            first: signed_push(id, &[1u8; 32]),
+            second: signed_push(id, &[0u8; 32]),
+        };
+        match execute(deps.as_mut(), mock_env(), mock_info("reporter", &[]), evidence_msg).unwrap_err() {
+            ContractError::EvidenceBeaconMismatch{round} => assert_eq!(round, ROUND),
+            _ => panic!("")
+        }
+
+        // a proof for another round
+        let mut other_round = signed_push(id, &[0u8; 32]);
+        other_round.round = ROUND + 1;
+        other_round.proof = ecvrf::prover::prove(&GENERATOR_SECRET_KEY, &randomness_preimage(&mock_env(), id, ROUND + 1, &[0u8; 32]));
+        let evidence_msg = ExecuteMsg::SubmitEvidence {
+            generator: GENERATOR.to_string(),
+            drand_response: drand_response(),
+            first: signed_push(id, &genuine_randomness()),
+            second: other_round,
+        };
+        match execute(deps.as_mut(), mock_env(), mock_info("reporter", &[]), evidence_msg).unwrap_err() {
+            ContractError::EvidenceBeaconMismatch{..} => {},
+            _ => panic!("")
+        }
+
+        // a beacon that does not verify
+        let mut forged_beacon = drand_response();
+        forged_beacon.previous_signature = Some(ROUND_SIGNATURE.to_string());
+        let evidence_msg = ExecuteMsg::SubmitEvidence {
+            generator: GENERATOR.to_string(),
+            drand_response: forged_beacon,
+            first: signed_push(id, &genuine_randomness()),
+            second: signed_push(id, &[0u8; 32]),
+        };
+        match execute(deps.as_mut(), mock_env(), mock_info("reporter", &[]), evidence_msg).unwrap_err() {
+            ContractError::DrandVerificationFailed{} => {},
+            _ => panic!("")
+        }
+
+        // the generator cannot collect the reporter reward for itself
+        let evidence_msg = ExecuteMsg::SubmitEvidence {
+            generator: GENERATOR.to_string(),
+            drand_response: drand_response(),
+            first: signed_push(id, &genuine_randomness()),
+            second: signed_push(id, &[0u8; 32]),
+        };
+        match execute(deps.as_mut(), mock_env(), mock_info(GENERATOR, &[]), evidence_msg).unwrap_err() {
+            ContractError::SelfReport{} => {},
+            _ => panic!("")
+        }
+
+        let generator: Generator = from_json(query(deps.as_ref(), mock_env(),
+            QueryMsg::Generator{addr: GENERATOR.to_string()}).unwrap()).unwrap();
+        assert_eq!(generator.stake, Uint128::new(1000));
+    }
+
+    #[test]
+    fn evidence_must_be_for_an_assigned_request_and_its_round() {
+        let mut deps = setup();
+        register_generator(&mut deps);
+
+        let evidence_msg = |request_id| ExecuteMsg::SubmitEvidence {
+            generator: GENERATOR.to_string(),
+            drand_response: drand_response(),
+            first: signed_push(request_id, &genuine_randomness()),
+            second: signed_push(request_id, &[0u8; 32]),
+        };
+
+        // proofs for a request that was never made
+        match execute(deps.as_mut(), mock_env(), mock_info("reporter", &[]), evidence_msg(1)).unwrap_err() {
+            ContractError::RequestNotFound{id} => assert_eq!(id, 1),
+            _ => panic!("")
+        }
+
+        // proofs for a round the request cannot be fulfilled with
+        let id = request_randomness_at(&mut deps, USER, ROUND_2515680_TIME + 30);
+        match execute(deps.as_mut(), mock_env(), mock_info("reporter", &[]), evidence_msg(id)).unwrap_err() {
+            ContractError::EvidenceRoundMismatch{round, min_round} => {
+                assert_eq!(round, ROUND);
+                assert_eq!(min_round, ROUND + 1);
+            },
+            _ => panic!("")
+        }
+
+        // proofs for a request assigned to another generator
+        let register_msg = ExecuteMsg::Register{public_key: ecvrf::prover::public_key(&[7u8; 32])};
+        execute(deps.as_mut(), mock_env(), mock_info("another", &stake()), register_msg).unwrap();
+        let id = request_randomness(&mut deps, USER);
+        assert_eq!(RANDOMNESS_REQUESTS.load(&deps.storage, id).unwrap().assigned_generator, Some(Addr::unchecked("another")));
+        match execute(deps.as_mut(), mock_env(), mock_info("reporter", &[]), evidence_msg(id)).unwrap_err() {
+            ContractError::GeneratorNotAssigned{id: rejected} => assert_eq!(rejected, id),
+            _ => panic!("")
+        }
+
+        // a generator that is not registered
+        let id = request_randomness(&mut deps, USER);
+        execute(deps.as_mut(), mock_env(), mock_info(GENERATOR, &[]), ExecuteMsg::DeleteGenerator{}).unwrap();
+        match execute(deps.as_mut(), mock_env(), mock_info("reporter", &[]), evidence_msg(id)).unwrap_err() {
+            ContractError::GeneratorNotRegistered{} => {},
+            _ => panic!("")
+        }
+    }
+
+    #[test]
+    fn update_reporter_reward() {
+        let mut deps = setup();
+
+        let update_msg = ExecuteMsg::UpdateReporterReward{reporter_reward: Decimal::percent(20)};
+        match execute(deps.as_mut(), mock_env(), mock_info(USER, &[]), update_msg.clone()).unwrap_err() {
+            ContractError::Unauthorized{} => {},
+            _ => panic!("")
+        }
+        execute(deps.as_mut(), mock_env(), mock_info(CREATOR, &[]), update_msg).unwrap();
+        assert_eq!(CONFIG.load(&deps.storage).unwrap().reporter_reward, Decimal::percent(20));
+
+        let update_msg = ExecuteMsg::UpdateReporterReward{reporter_reward: Decimal::percent(101)};
+        match execute(deps.as_mut(), mock_env(), mock_info(CREATOR, &[]), update_msg).unwrap_err() {
+            ContractError::InvalidReporterReward{} => {},
+            _ => panic!("")
+        }
+    }
+
+    #[test]
+    fn update_max_num_words() {
+        let mut deps = setup();
+
+        let update_msg = ExecuteMsg::UpdateMaxNumWords{max_num_words: 2};
+        match execute(deps.as_mut(), mock_env(), mock_info(USER, &[]), update_msg.clone()).unwrap_err() {
+            ContractError::Unauthorized{} => {},
+            _ => panic!("")
+        }
+        execute(deps.as_mut(), mock_env(), mock_info(CREATOR, &[]), update_msg).unwrap();
+        assert_eq!(CONFIG.load(&deps.storage).unwrap().max_num_words, 2);
+
+        let update_msg = ExecuteMsg::UpdateMaxNumWords{max_num_words: 0};
+        match execute(deps.as_mut(), mock_env(), mock_info(CREATOR, &[]), update_msg).unwrap_err() {
+            ContractError::MustNotBeZero{field} => assert_eq!(field, "max_num_words"),
+            _ => panic!("")
+        }
+    }
+
+    #[test]
+    fn update_max_callback_gas_limit() {
+        let mut deps = setup();
+
+        let update_msg = ExecuteMsg::UpdateMaxCallbackGasLimit{max_callback_gas_limit: 100_000};
+        match execute(deps.as_mut(), mock_env(), mock_info(USER, &[]), update_msg.clone()).unwrap_err() {
+            ContractError::Unauthorized{} => {},
+            _ => panic!("")
+        }
+        execute(deps.as_mut(), mock_env(), mock_info(CREATOR, &[]), update_msg).unwrap();
+        assert_eq!(CONFIG.load(&deps.storage).unwrap().max_callback_gas_limit, 100_000);
+
+        let update_msg = ExecuteMsg::UpdateMaxCallbackGasLimit{max_callback_gas_limit: 0};
+        match execute(deps.as_mut(), mock_env(), mock_info(CREATOR, &[]), update_msg).unwrap_err() {
+            ContractError::MustNotBeZero{field} => assert_eq!(field, "max_callback_gas_limit"),
+            _ => panic!("")
+        }
+    }
+
+    #[test]
+    fn register_requires_minimum_stake() {
+        let mut deps = setup();
+
+        for funds in [vec![], coins(999, "ustake"), coins(1000, "uatom"), vec![coin(1000, "ustake"), coin(1, "uatom")]] {
+            let register_msg = ExecuteMsg::Register{public_key: GENERATOR_PUBLIC_KEY.to_string()};
+            match execute(deps.as_mut(), mock_env(), mock_info(GENERATOR, &funds), register_msg).unwrap_err() {
+                ContractError::InsufficientStake{min_stake} => assert_eq!(min_stake, "1000ustake"),
+                _ => panic!("")
+            }
+        }
+
+        let register_msg = ExecuteMsg::Register{public_key: GENERATOR_PUBLIC_KEY.to_string()};
+        execute(deps.as_mut(), mock_env(), mock_info(GENERATOR, &coins(1500, "ustake")), register_msg).unwrap();
+
+        let generator: Generator = from_json(query(deps.as_ref(), mock_env(),
+            QueryMsg::Generator{addr: GENERATOR.to_string()}).unwrap()).unwrap();
+        assert_eq!(generator.stake, Uint128::new(1500));
+    }
+
+    #[test]
+    fn deleted_generator_claims_stake_after_unbonding() {
+        let mut deps = setup();
+        register_generator(&mut deps);
+
+        execute(deps.as_mut(), mock_env(), mock_info(GENERATOR, &[]), ExecuteMsg::DeleteGenerator{}).unwrap();
+        query(deps.as_ref(), mock_env(), QueryMsg::Generator{addr: GENERATOR.to_string()}).unwrap_err();
+
+        match execute(deps.as_mut(), mock_env(), mock_info(GENERATOR, &[]), ExecuteMsg::ClaimUnbonded{}).unwrap_err() {
+            ContractError::StakeNotUnbonded{..} => {},
+            _ => panic!("")
+        }
+
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(UNBONDING_PERIOD);
+        let res = execute(deps.as_mut(), env.clone(), mock_info(GENERATOR, &[]), ExecuteMsg::ClaimUnbonded{}).unwrap();
+        assert_eq!(res.messages, vec![SubMsg::new(BankMsg::Send {
+            to_address: GENERATOR.to_string(),
+            amount: stake(),
+        })]);
+
+        // nothing is left to claim
+        match execute(deps.as_mut(), env, mock_info(GENERATOR, &[]), ExecuteMsg::ClaimUnbonded{}).unwrap_err() {
+            ContractError::NothingToClaim{} => {},
+            _ => panic!("")
+        }
+    }
+
+    #[test]
+    fn update_staking_config() {
+        let mut deps = setup();
+
+        let mut new_staking = staking();
+        new_staking.min_stake = Uint128::new(5000);
+
+        let update_msg = ExecuteMsg::UpdateStakingConfig{staking: new_staking.clone()};
+        match execute(deps.as_mut(), mock_env(), mock_info(USER, &[]), update_msg.clone()).unwrap_err() {
+            ContractError::Unauthorized{} => {},
+            _ => panic!("")
+        }
+        execute(deps.as_mut(), mock_env(), mock_info(CREATOR, &[]), update_msg).unwrap();
+
+        let config: Config = from_json(query(deps.as_ref(), mock_env(), QueryMsg::Config{}).unwrap()).unwrap();
+        assert_eq!(config.staking, new_staking);
+
+        let mut invalid = staking();
+        invalid.slash_fraction = Decimal::percent(101);
+        match execute(deps.as_mut(), mock_env(), mock_info(CREATOR, &[]), ExecuteMsg::UpdateStakingConfig{staking: invalid}).unwrap_err() {
+            ContractError::InvalidStakingConfig{..} => {},
+            _ => panic!("")
+        }
+
+        let mut invalid = staking();
+        invalid.denom = "uatom".to_string();
+        match execute(deps.as_mut(), mock_env(), mock_info(CREATOR, &[]), ExecuteMsg::UpdateStakingConfig{staking: invalid}).unwrap_err() {
+            ContractError::StakingDenomChanged{} => {},
+            _ => panic!("")
+        }
+    }
+
+    #[test]
+    fn generator_register_rejects_public_key_in_use() {
+        let mut deps = setup();
+        register_generator(&mut deps);
+
+        // also when spelled in upper case hex
+        for public_key in [GENERATOR_PUBLIC_KEY.to_string(), GENERATOR_PUBLIC_KEY.to_uppercase()] {
+            let register_msg = ExecuteMsg::Register{public_key};
+            match execute(deps.as_mut(), mock_env(), mock_info("thief", &stake()), register_msg).unwrap_err() {
+                ContractError::PublicKeyInUse{} => {},
+                _ => panic!("")
+            }
+        }
+
+        // the key is free again once its generator left
+        execute(deps.as_mut(), mock_env(), mock_info(GENERATOR, &[]), ExecuteMsg::DeleteGenerator{}).unwrap();
+        let register_msg = ExecuteMsg::Register{public_key: GENERATOR_PUBLIC_KEY.to_string()};
+        execute(deps.as_mut(), mock_env(), mock_info("thief", &stake()), register_msg).unwrap();
+    }
+
+    #[test]
+    fn copied_proof_cannot_be_pushed_by_another_generator() {
+        let mut deps = setup();
+        register_generator(&mut deps);
+        let register_msg = ExecuteMsg::Register{public_key: ecvrf::prover::public_key(&[7u8; 32])};
+        execute(deps.as_mut(), mock_env(), mock_info("thief", &stake()), register_msg).unwrap();
+        let id = request_randomness(&mut deps, USER);
+        assert_eq!(RANDOMNESS_REQUESTS.load(&deps.storage, id).unwrap().assigned_generator, Some(Addr::unchecked(GENERATOR)));
+
+        let push_randomness_msg = |request_id| ExecuteMsg::PushRandomness {
+            proof: prove_request(id),
+            drand_response: drand_response(),
+            request_id,
+        };
+        match execute(deps.as_mut(), mock_env(), mock_info("thief", &[]), push_randomness_msg(Some(id))).unwrap_err() {
+            ContractError::GeneratorNotAssigned{id: rejected} => assert_eq!(rejected, id),
+            _ => panic!("")
+        }
+        match execute(deps.as_mut(), mock_env(), mock_info("thief", &[]), push_randomness_msg(None)).unwrap_err() {
+            ContractError::NoRequestToFulfill{round} => assert_eq!(round, ROUND),
+            _ => panic!("")
+        }
+        assert_eq!(RANDOMNESS_REQUESTS.load(&deps.storage, id).unwrap().status, RequestStatus::Pending);
+
+        execute(deps.as_mut(), mock_env(), mock_info(GENERATOR, &[]), push_randomness_msg(None)).unwrap();
+        assert_eq!(RANDOMNESS_REQUESTS.load(&deps.storage, id).unwrap().status, RequestStatus::Fulfilled);
+    }
+
+    #[test]
+    fn requests_are_assigned_round_robin() {
+        let mut deps = setup();
+
+        let request_randomness_msg = ExecuteMsg::RequestRandomness {
+            user_seed: Binary::from(USER_SEED),
+            callback_data: Binary::default(),
+            time_set: ROUND_2515680_TIME,
+            num_words: 1,
+            mode: RandomnessMode::Vrf,
+            expires: None,
+            callback_contract: None,
+            callback_gas_limit: None,
+            delivery: Delivery::Callback,
+        };
+        match execute(deps.as_mut(), mock_env(), mock_info(USER, &[]), request_randomness_msg).unwrap_err() {
+            ContractError::NoGenerator{} => {},
+            _ => panic!("")
+        }
+
+        register_generator(&mut deps);
+        let register_msg = ExecuteMsg::Register{public_key: ecvrf::prover::public_key(&[7u8; 32])};
+        execute(deps.as_mut(), mock_env(), mock_info("another", &stake()), register_msg).unwrap();
+
+        let ids = (0..3).map(|_| request_randomness(&mut deps, USER)).collect::<Vec<_>>();
+        let assigned = ids.iter()
+            .map(|id| RANDOMNESS_REQUESTS.load(&deps.storage, *id).unwrap().assigned_generator.unwrap().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(assigned, vec!["another", GENERATOR, "another"]);
+    }
+
+    #[test]
+    fn generator_register_rejects_invalid_public_key() {
+        let mut deps = setup();
+
+        // secp256k1 keys are no VRF keys
+        let register_msg = ExecuteMsg::Register{
+            public_key: "03224d723fb48f1c247e105bca7853fb5c711e59fc824f5e581614d80bfbde1c99".to_string(),
+        };
+
+        match execute(deps.as_mut(), mock_env(), mock_info(GENERATOR, &stake()), register_msg).unwrap_err() {
+            ContractError::InvalidLength{field, expected, ..} => {
+                assert_eq!(field, "public_key");
+                assert_eq!(expected, 32);
+            },
+            _ => panic!("")
+        }
+    }
+
+    #[test]
+    fn user_request_randomness_success() {
+        let mut deps = setup();
+        register_generator(&mut deps);
+
+        let time_set = 100;
+
+        let request_randomness_msg = ExecuteMsg::RequestRandomness {
+            user_seed: Binary::from(USER_SEED),
+            callback_data: Binary::from(b"game 7"),
+            time_set,
+            num_words: 1,
+            mode: RandomnessMode::Vrf,
+            delivery: Delivery::Callback,
+            expires: None,
+            callback_contract: None,
+            callback_gas_limit: None,
+        };
+
+        let raw_push = execute(deps.as_mut(), mock_env(), mock_info(USER, &[]), request_randomness_msg).unwrap();
+        assert_eq!(raw_push, Response::new()
+            .set_data(to_json_binary(&RequestRandomnessResponse{request_id: 1}).unwrap())
+            .add_attribute("action", "request randomness".to_string())
+            .add_attribute("request_id", "1".to_string()));
+
+        let request = RANDOMNESS_REQUESTS.load(&deps.storage, 1).unwrap();
+
+        assert_eq!(request.id, 1);
+        assert_eq!(request.user_seed, Binary::from(USER_SEED));
+        assert_eq!(request.callback_data, Binary::from(b"game 7"));
+        assert_eq!(request.time, time_set);
+        assert_eq!(request.user.as_ref(), USER);
+        assert_eq!(request.status, RequestStatus::Pending);
+    }
+
+    fn request_randomness(deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>, user: &str) -> u64 {
+        request_randomness_at(deps, user, ROUND_2515680_TIME)
+    }
+
+    fn request_randomness_at(deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>, user: &str, time_set: u128) -> u64 {
+        let request_randomness_msg = ExecuteMsg::RequestRandomness {
+            user_seed: Binary::from(USER_SEED),
+            callback_data: Binary::default(),
+            time_set,
+            num_words: 1,
+            mode: RandomnessMode::Vrf,
+            delivery: Delivery::Callback,
+            expires: None,
+            callback_contract: None,
+            callback_gas_limit: None,
+        };
+
+        let res = execute(deps.as_mut(), mock_env(), mock_info(user, &[]), request_randomness_msg).unwrap();
+        let data: RequestRandomnessResponse = from_json(res.data.unwrap()).unwrap();
+        data.request_id
+    }
+
+    fn drand_response() -> DrandResponse {
+        DrandResponse {
+            round: ROUND,
+            signature: ROUND_SIGNATURE.to_string(),
+            previous_signature: Some(ROUND_PREVIOUS_SIGNATURE.to_string()),
+        }
+    }
+
+    fn request_alpha(request_id: u64) -> Vec<u8> {
+        let drand_randomness = derive_randomness_from_signature(&hex::decode(ROUND_SIGNATURE).unwrap());
+        randomness_preimage(&mock_env(), request_id, ROUND, &drand_randomness)
+    }
+
+    /// VRF proof of the test generator for `request_id` and drand round 2515680
+    fn prove_request(request_id: u64) -> String {
+        ecvrf::prover::prove(&GENERATOR_SECRET_KEY, &request_alpha(request_id))
+    }
+
+    fn push_randomness(deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>, request_id: Option<u64>) -> Result<Response, ContractError> {
+        // the proof has to be made for the request the push is going to fulfill
+        let target = request_id
+            .or_else(|| load_request_to_fulfill(&deps.storage, &mock_env().block, &Addr::unchecked(GENERATOR), None, ROUND).ok().flatten().map(|request| request.id))
+            .unwrap_or_default();
+
+        let push_randomness_msg = ExecuteMsg::PushRandomness {
+            proof: prove_request(target),
+            drand_response: drand_response(),
+            request_id,
+        };
+
+        execute(deps.as_mut(), mock_env(), mock_info(GENERATOR, &[]), push_randomness_msg)
+    }
+
+    fn register_generator(deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>) {
+        let register_msg = ExecuteMsg::Register{
+            public_key: GENERATOR_PUBLIC_KEY.to_string(),
+        };
+
+        execute(deps.as_mut(), mock_env(), mock_info(GENERATOR, &stake()), register_msg).unwrap();
+    }
+
+    #[test]
+    fn request_ids_are_increasing() {
+        let mut deps = setup();
+        register_generator(&mut deps);
+
+        assert_eq!(request_randomness(&mut deps, USER), 1);
+        assert_eq!(request_randomness(&mut deps, USER), 2);
+        assert_eq!(request_randomness(&mut deps, CREATOR), 3);
+    }
+
+    #[test]
+    fn push_randomness_fulfills_oldest_request_first() {
+        let mut deps = setup();
+        register_generator(&mut deps);
+
+        let first = request_randomness(&mut deps, USER);
+        let second = request_randomness(&mut deps, USER);
+
+        let res = push_randomness(&mut deps, None).unwrap();
+        assert_eq!(res.attributes[1].value, first.to_string());
+
+        assert_eq!(RANDOMNESS_REQUESTS.load(&deps.storage, first).unwrap().status, RequestStatus::Fulfilled);
+        assert_eq!(RANDOMNESS_REQUESTS.load(&deps.storage, second).unwrap().status, RequestStatus::Pending);
+
+        let res = push_randomness(&mut deps, None).unwrap();
+        assert_eq!(res.attributes[1].value, second.to_string());
+        assert_eq!(RANDOMNESS_REQUESTS.load(&deps.storage, second).unwrap().status, RequestStatus::Fulfilled);
+    }
+
+    #[test]
+    fn push_randomness_fulfills_explicit_request() {
+        let mut deps = setup();
+        register_generator(&mut deps);
+
+        let first = request_randomness(&mut deps, USER);
+        let second = request_randomness(&mut deps, USER);
+
+        push_randomness(&mut deps, Some(second)).unwrap();
+
+        assert_eq!(RANDOMNESS_REQUESTS.load(&deps.storage, first).unwrap().status, RequestStatus::Pending);
+        assert_eq!(RANDOMNESS_REQUESTS.load(&deps.storage, second).unwrap().status, RequestStatus::Fulfilled);
+
+        match push_randomness(&mut deps, Some(second)).unwrap_err() {
+            ContractError::ReplayedPush{request_id} => assert_eq!(request_id, second),
+            _ => panic!("")
+        }
+
+        match push_randomness(&mut deps, Some(42)).unwrap_err() {
+            ContractError::RequestNotFound{id} => assert_eq!(id, 42),
+            _ => panic!("")
+        }
+    }
+
+    #[test]
+    fn push_randomness_rejects_replays() {
+        let mut deps = setup();
+        register_generator(&mut deps);
+
+        let first = request_randomness(&mut deps, USER);
+        let second = request_randomness(&mut deps, USER);
+
+        let push_randomness_msg = ExecuteMsg::PushRandomness {
+            proof: prove_request(first),
+            drand_response: drand_response(),
+            request_id: None,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info(GENERATOR, &[]), push_randomness_msg.clone()).unwrap();
+        assert_eq!(CONSUMED_ROUNDS.load(&deps.storage, (ROUND, first)).unwrap().as_ref(), GENERATOR);
+
+        // the same proof is rejected, even while another request is pending
+        match execute(deps.as_mut(), mock_env(), mock_info(GENERATOR, &[]), push_randomness_msg).unwrap_err() {
+            ContractError::ReplayedPush{request_id} => assert_eq!(request_id, first),
+            _ => panic!("")
+        }
+
+        // so is an already consumed round for the request
+        let push_randomness_msg = ExecuteMsg::PushRandomness {
+            proof: prove_request(second),
+            drand_response: drand_response(),
+            request_id: Some(first),
+        };
+        match execute(deps.as_mut(), mock_env(), mock_info(GENERATOR, &[]), push_randomness_msg).unwrap_err() {
+            ContractError::ReplayedPush{request_id} => assert_eq!(request_id, first),
+            _ => panic!("")
+        }
+
+        assert_eq!(RANDOMNESS_REQUESTS.load(&deps.storage, second).unwrap().status, RequestStatus::Pending);
+        push_randomness(&mut deps, Some(second)).unwrap();
+    }
+
+    #[test]
+    fn push_randomness_dispatches_callback() {
+        let mut deps = setup();
+        register_generator(&mut deps);
+
+        let id = request_randomness(&mut deps, USER);
+        let res = push_randomness(&mut deps, None).unwrap();
+
+        assert_eq!(res.messages.len(), 1);
+        assert_eq!(res.messages[0].id, id);
+        assert_eq!(res.messages[0].reply_on, ReplyOn::Error);
+        assert_eq!(res.messages[0].gas_limit, Some(MAX_CALLBACK_GAS_LIMIT));
+        match &res.messages[0].msg {
+            CosmosMsg::Wasm(WasmMsg::Execute{contract_addr, ..}) => assert_eq!(contract_addr, USER),
+            _ => panic!("")
+        }
+    }
+
+    #[test]
+    fn push_randomness_calls_back_named_contract_with_gas_limit() {
+        let mut deps = setup();
+        register_generator(&mut deps);
+
+        let request_msg = |callback_gas_limit| ExecuteMsg::RequestRandomness {
+            user_seed: Binary::from(USER_SEED),
+            callback_data: Binary::default(),
+            time_set: ROUND_2515680_TIME,
+            num_words: 1,
+            mode: RandomnessMode::Vrf,
+            delivery: Delivery::Callback,
+            expires: None,
+            callback_contract: Some("consumer".to_string()),
+            callback_gas_limit,
+        };
+
+        for callback_gas_limit in [0, MAX_CALLBACK_GAS_LIMIT + 1] {
+            match execute(deps.as_mut(), mock_env(), mock_info(USER, &[]), request_msg(Some(callback_gas_limit))).unwrap_err() {
+                ContractError::InvalidCallbackGasLimit{max} => assert_eq!(max, MAX_CALLBACK_GAS_LIMIT),
+                _ => panic!("")
+            }
+        }
+
+        execute(deps.as_mut(), mock_env(), mock_info(USER, &[]), request_msg(Some(100_000))).unwrap();
+        let res = push_randomness(&mut deps, None).unwrap();
+
+        assert_eq!(res.messages[0].gas_limit, Some(100_000));
+        match &res.messages[0].msg {
+            CosmosMsg::Wasm(WasmMsg::Execute{contract_addr, ..}) => assert_eq!(contract_addr, "consumer"),
+            _ => panic!("")
+        }
+        assert_eq!(RANDOMNESS_REQUESTS.load(&deps.storage, 1).unwrap().user.as_str(), USER);
+    }
+
+    #[test]
+    fn push_randomness_delivers_requested_words() {
+        let mut deps = setup();
+        register_generator(&mut deps);
+
+        for num_words in [0, MAX_NUM_WORDS + 1] {
+            let request_randomness_msg = ExecuteMsg::RequestRandomness {
+                user_seed: Binary::from(USER_SEED),
+                callback_data: Binary::default(),
+                time_set: ROUND_2515680_TIME,
+                num_words,
+                mode: RandomnessMode::Vrf,
+                delivery: Delivery::Callback,
+                expires: None,
+                callback_contract: None,
+                callback_gas_limit: None,
+            };
+            match execute(deps.as_mut(), mock_env(), mock_info(USER, &[]), request_randomness_msg).unwrap_err() {
+                ContractError::InvalidNumWords{max} => assert_eq!(max, MAX_NUM_WORDS),
+                _ => panic!("")
+            }
+        }
+
+        let request_randomness_msg = ExecuteMsg::RequestRandomness {
+            user_seed: Binary::from(USER_SEED),
+            callback_data: Binary::default(),
+            time_set: ROUND_2515680_TIME,
+            num_words: 5,
+            mode: RandomnessMode::Vrf,
+            delivery: Delivery::Callback,
+            expires: None,
+            callback_contract: None,
+            callback_gas_limit: None,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info(USER, &[]), request_randomness_msg).unwrap();
+        let res = push_randomness(&mut deps, None).unwrap();
+
+        let result = FULFILLED_RESULTS.load(&deps.storage, 1).unwrap();
+        let words = match &res.messages[0].msg {
+            CosmosMsg::Wasm(WasmMsg::Execute{msg, ..}) => match from_json(msg).unwrap() {
+                CallBackMsg::ReturnRandomness{randomness, ..} => randomness,
+            },
+            _ => panic!("")
+        };
+        assert_eq!(words, result.words);
+        assert_eq!(words.len(), 5);
+        assert!(words.iter().all(|word| word.len() == 64));
+
+        // words are distinct and the first one differs from the seed
+        let mut distinct = words.clone();
+        distinct.sort();
+        distinct.dedup();
+        assert_eq!(distinct.len(), 5);
+        assert_ne!(words[0], result.randomness);
+        assert_eq!(words[4], hex::encode(&expand_words(&hex::decode(&result.randomness).unwrap(), 5)[4]));
+    }
+
+    #[test]
+    fn stored_randomness_is_queried_without_callback() {
+        let mut deps = setup_with_fee(Some(coin(100, "uatom")));
+        register_generator(&mut deps);
+
+        let request_randomness_msg = ExecuteMsg::RequestRandomness {
+            user_seed: Binary::from(USER_SEED),
+            callback_data: Binary::default(),
+            time_set: ROUND_2515680_TIME,
+            num_words: 2,
+            mode: RandomnessMode::Vrf,
+            delivery: Delivery::Store,
+            expires: None,
+            callback_contract: None,
+            callback_gas_limit: None,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info(USER, &coins(100, "uatom")), request_randomness_msg).unwrap();
+
+        query(deps.as_ref(), mock_env(), QueryMsg::RandomnessResult{id: 1}).unwrap_err();
+
+        // only the generator is paid
+        let res = push_randomness(&mut deps, None).unwrap();
+        assert_eq!(res.messages.len(), 1);
+        assert_eq!(res.messages[0].reply_on, ReplyOn::Never);
+
+        let result: RandomnessResultResponse = from_json(query(deps.as_ref(), mock_env(), QueryMsg::RandomnessResult{id: 1}).unwrap()).unwrap();
+        assert_eq!(result, RandomnessResultResponse{
+            request_id: 1,
+            randomness: FULFILLED_RESULTS.load(&deps.storage, 1).unwrap().words,
+            round: ROUND,
+            proof: prove_request(1),
+        });
+        assert_eq!(result.randomness.len(), 2);
+        assert_eq!(RANDOMNESS_REQUESTS.load(&deps.storage, 1).unwrap().status, RequestStatus::Fulfilled);
+    }
+
+    #[test]
+    fn failed_callback_is_recorded() {
+        let mut deps = setup();
+        register_generator(&mut deps);
+
+        let id = request_randomness(&mut deps, USER);
+        push_randomness(&mut deps, None).unwrap();
+
+        let reply_msg = Reply {
+            id,
+            result: SubMsgResult::Err("consumer out of gas".to_string()),
+        };
+        reply(deps.as_mut(), mock_env(), reply_msg).unwrap();
+
+        let raw = query(deps.as_ref(), mock_env(), QueryMsg::Request{id}).unwrap();
+        let request: RandomnessRequest = from_json(raw).unwrap();
+
+        assert_eq!(request.status, RequestStatus::Failed);
+        assert_eq!(request.callback_error, Some("consumer out of gas".to_string()));
+    }
+
+    #[test]
+    fn delivered_callback_keeps_request_fulfilled() {
+        let mut deps = setup();
+        register_generator(&mut deps);
+
+        let id = request_randomness(&mut deps, USER);
+        push_randomness(&mut deps, None).unwrap();
+
+        let reply_msg = Reply {
+            id,
+            result: SubMsgResult::Ok(SubMsgResponse{events: vec![], data: None}),
+        };
+        reply(deps.as_mut(), mock_env(), reply_msg).unwrap();
+
+        let request = RANDOMNESS_REQUESTS.load(&deps.storage, id).unwrap();
+        assert_eq!(request.status, RequestStatus::Fulfilled);
+        assert_eq!(request.callback_error, None);
+    }
+
+    #[test]
+    fn request_randomness_maps_time_set_to_round() {
+        let mut deps = setup();
+        register_generator(&mut deps);
+
+        let id = request_randomness_at(&mut deps, USER, ROUND_2515680_TIME);
+        assert_eq!(RANDOMNESS_REQUESTS.load(&deps.storage, id).unwrap().min_round, 2515680);
+
+        let id = request_randomness_at(&mut deps, USER, ROUND_2515680_TIME + 1);
+        assert_eq!(RANDOMNESS_REQUESTS.load(&deps.storage, id).unwrap().min_round, 2515681);
+
+        // a time in the past still requires a round emitted after the request
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(ROUND_2515680_TIME as u64);
+        let request_randomness_msg = ExecuteMsg::RequestRandomness {
+            user_seed: Binary::from(USER_SEED),
+            callback_data: Binary::default(),
+            time_set: 100,
+            num_words: 1,
+            mode: RandomnessMode::Vrf,
+            delivery: Delivery::Callback,
+            expires: None,
+            callback_contract: None,
+            callback_gas_limit: None,
+        };
+        execute(deps.as_mut(), env, mock_info(USER, &[]), request_randomness_msg).unwrap();
+        assert_eq!(RANDOMNESS_REQUESTS.load(&deps.storage, 3).unwrap().min_round, 2515681);
+    }
+
+    #[test]
+    fn request_randomness_rejects_time_set_beyond_u64() {
+        let mut deps = setup();
+        register_generator(&mut deps);
+
+        let request_randomness_msg = ExecuteMsg::RequestRandomness {
+            user_seed: Binary::from(USER_SEED),
+            callback_data: Binary::default(),
+            time_set: u64::MAX as u128 + 1,
+            num_words: 1,
+            mode: RandomnessMode::Vrf,
+            expires: None,
+            callback_contract: None,
+            callback_gas_limit: None,
+            delivery: Delivery::Callback,
+        };
+        match execute(deps.as_mut(), mock_env(), mock_info(USER, &[]), request_randomness_msg).unwrap_err() {
+            ContractError::InvalidTimeSet{time_set} => assert_eq!(time_set, u64::MAX as u128 + 1),
+            _ => panic!("")
+        }
+
+        // the rejected request did not take an id
+        assert_eq!(request_randomness(&mut deps, USER), 1);
+    }
+
+    #[test]
+    fn push_randomness_rejects_early_round() {
+        let mut deps = setup();
+        register_generator(&mut deps);
+
+        let scheduled = request_randomness_at(&mut deps, USER, ROUND_2515680_TIME + 1);
+
+        match push_randomness(&mut deps, Some(scheduled)).unwrap_err() {
+            ContractError::DrandRoundTooEarly{round, min_round} => {
+                assert_eq!(round, 2515680);
+                assert_eq!(min_round, 2515681);
+            },
+            _ => panic!("")
+        }
+
+        // the oldest request that the round may fulfill is served instead
+        let ready = request_randomness_at(&mut deps, USER, ROUND_2515680_TIME);
+        let res = push_randomness(&mut deps, None).unwrap();
+        assert_eq!(res.attributes[1].value, ready.to_string());
+
+        assert_eq!(RANDOMNESS_REQUESTS.load(&deps.storage, scheduled).unwrap().status, RequestStatus::Pending);
+        assert_eq!(RANDOMNESS_REQUESTS.load(&deps.storage, ready).unwrap().status, RequestStatus::Fulfilled);
+    }
+
+    #[test]
+    fn push_randomness_rejects_late_round() {
+        let mut deps = setup();
+        register_generator(&mut deps);
+
+        let overdue = request_randomness_at(&mut deps, USER, ROUND_2515680_TIME - 30);
+
+        match push_randomness(&mut deps, Some(overdue)).unwrap_err() {
+            ContractError::DrandRoundTooLate{round, min_round} => {
+                assert_eq!(round, 2515680);
+                assert_eq!(min_round, 2515679);
+            },
+            _ => panic!("")
+        }
+
+        // only requests scheduled for the round are served
+        match push_randomness(&mut deps, None).unwrap_err() {
+            ContractError::NoRequestToFulfill{round} => assert_eq!(round, 2515680),
+            _ => panic!("")
+        }
+    }
+
+    #[test]
+    fn query_config_and_generators() {
+        let mut deps = setup();
+        register_generator(&mut deps);
+
+        let config: Config = from_json(query(deps.as_ref(), mock_env(), QueryMsg::Config{}).unwrap()).unwrap();
+        assert_eq!(config.owner.as_ref(), CREATOR);
+
+        let generator: Generator = from_json(query(deps.as_ref(), mock_env(),
+            QueryMsg::Generator{addr: GENERATOR.to_string()}).unwrap()).unwrap();
+        assert_eq!(generator.addr.as_ref(), GENERATOR);
+
+        let register_msg = ExecuteMsg::Register{
+            public_key: ecvrf::prover::public_key(&[7u8; 32]),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("another", &stake()), register_msg).unwrap();
+
+        let res: GeneratorsResponse = from_json(query(deps.as_ref(), mock_env(),
+            QueryMsg::ListGenerators{start_after: None, limit: Some(1)}).unwrap()).unwrap();
+        assert_eq!(res.generators.len(), 1);
+        assert_eq!(res.generators[0].addr.as_ref(), "another");
+
+        let res: GeneratorsResponse = from_json(query(deps.as_ref(), mock_env(),
+            QueryMsg::ListGenerators{start_after: Some("another".to_string()), limit: None}).unwrap()).unwrap();
+        assert_eq!(res.generators.len(), 1);
+        assert_eq!(res.generators[0].addr.as_ref(), GENERATOR);
+    }
+
+    #[test]
+    fn query_requests_and_results() {
+        let mut deps = setup();
+        register_generator(&mut deps);
+
+        let first = request_randomness(&mut deps, USER);
+        let second = request_randomness(&mut deps, CREATOR);
+        let third = request_randomness(&mut deps, USER);
+
+        push_randomness(&mut deps, None).unwrap();
+
+        let res: RequestsResponse = from_json(query(deps.as_ref(), mock_env(),
+            QueryMsg::PendingRequests{start_after: None, limit: None}).unwrap()).unwrap();
+        assert_eq!(res.requests.iter().map(|r| r.id).collect::<Vec<_>>(), vec![second, third]);
+
+        let res: RequestsResponse = from_json(query(deps.as_ref(), mock_env(),
+            QueryMsg::PendingRequests{start_after: Some(second), limit: None}).unwrap()).unwrap();
+        assert_eq!(res.requests.iter().map(|r| r.id).collect::<Vec<_>>(), vec![third]);
+
+        let res: RequestsResponse = from_json(query(deps.as_ref(), mock_env(),
+            QueryMsg::RequestsByUser{user: USER.to_string(), start_after: None, limit: None}).unwrap()).unwrap();
+        assert_eq!(res.requests.iter().map(|r| r.id).collect::<Vec<_>>(), vec![first, third]);
+
+        let res: RequestsResponse = from_json(query(deps.as_ref(), mock_env(),
+            QueryMsg::RequestsByUser{user: USER.to_string(), start_after: None, limit: Some(1)}).unwrap()).unwrap();
+        assert_eq!(res.requests.iter().map(|r| r.id).collect::<Vec<_>>(), vec![first]);
+
+        let res: RequestsResponse = from_json(query(deps.as_ref(), mock_env(),
+            QueryMsg::RequestsByUser{user: USER.to_string(), start_after: Some(first), limit: None}).unwrap()).unwrap();
+        assert_eq!(res.requests.iter().map(|r| r.id).collect::<Vec<_>>(), vec![third]);
+
+        let result: FulfilledResult = from_json(query(deps.as_ref(), mock_env(),
+            QueryMsg::FulfilledResult{id: first}).unwrap()).unwrap();
+        assert_eq!(result.request_id, first);
+        assert_eq!(result.round, 2515680);
+        assert_eq!(result.generator, Some(Addr::unchecked(GENERATOR)));
+
+        query(deps.as_ref(), mock_env(), QueryMsg::FulfilledResult{id: second}).unwrap_err();
+    }
+
+    #[test]
+    fn push_randomness_returns_callback_data() {
+        let mut deps = setup();
+        register_generator(&mut deps);
+
+        let request_randomness_msg = ExecuteMsg::RequestRandomness {
+            user_seed: Binary::from(USER_SEED),
+            callback_data: Binary::from(b"{\"game\":7}"),
+            time_set: ROUND_2515680_TIME,
+            num_words: 1,
+            mode: RandomnessMode::Vrf,
+            delivery: Delivery::Callback,
+            expires: None,
+            callback_contract: None,
+            callback_gas_limit: None,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info(USER, &[]), request_randomness_msg).unwrap();
+
+        let res = push_randomness(&mut deps, None).unwrap();
+        match &res.messages[0].msg {
+            CosmosMsg::Wasm(WasmMsg::Execute{msg, ..}) => match from_json(msg).unwrap() {
+                CallBackMsg::ReturnRandomness{callback_data, ..} => assert_eq!(callback_data, Binary::from(b"{\"game\":7}")),
+            },
+            _ => panic!("")
+        }
+    }
+
+    #[test]
+    fn instantiate_rejects_invalid_drand_config() {
+        let mut deps = mock_dependencies();
+
+        let mut drand = leo_mainnet();
+        drand.public_key = "aabb".to_string();
+        let err = instantiate(deps.as_mut(), mock_env(), mock_info(CREATOR, &[]), InstantiateMsg{drand, request_fee: None, staking: staking(), reporter_reward: Decimal::percent(50), commit_reveal: commit_reveal(), request_timeout: None, max_num_words: MAX_NUM_WORDS, max_callback_gas_limit: MAX_CALLBACK_GAS_LIMIT}).unwrap_err();
+        match err {
+            ContractError::InvalidLength{field, ..} => assert_eq!(field, "public_key"),
+            _ => panic!("")
+        }
+
+        let mut drand = leo_mainnet();
+        drand.period = 0;
+        let err = instantiate(deps.as_mut(), mock_env(), mock_info(CREATOR, &[]), InstantiateMsg{drand, request_fee: None, staking: staking(), reporter_reward: Decimal::percent(50), commit_reveal: commit_reveal(), request_timeout: None, max_num_words: MAX_NUM_WORDS, max_callback_gas_limit: MAX_CALLBACK_GAS_LIMIT}).unwrap_err();
+        match err {
+            ContractError::InvalidDrandPeriod{} => {},
+            _ => panic!("")
+        }
+
+        // G1 signature schemes have their public key on G2
+        let mut drand = leo_mainnet();
+        drand.scheme = DrandScheme::BlsUnchainedG1Rfc9380;
+        let err = instantiate(deps.as_mut(), mock_env(), mock_info(CREATOR, &[]), InstantiateMsg{drand, request_fee: None, staking: staking(), reporter_reward: Decimal::percent(50), commit_reveal: commit_reveal(), request_timeout: None, max_num_words: MAX_NUM_WORDS, max_callback_gas_limit: MAX_CALLBACK_GAS_LIMIT}).unwrap_err();
+        match err {
+            ContractError::InvalidLength{field, expected, ..} => {
+                assert_eq!(field, "public_key");
+                assert_eq!(expected, 96);
+            },
+            _ => panic!("")
+        }
+    }
+
+    #[test]
+    fn push_randomness_requires_previous_signature_when_chained() {
+        let mut deps = setup();
+        register_generator(&mut deps);
+
+        let id = request_randomness(&mut deps, USER);
+
+        let mut drand_response = drand_response();
+        drand_response.previous_signature = None;
+
+        let msg = ExecuteMsg::PushRandomness{
+            proof: prove_request(id),
+            drand_response,
+            request_id: None,
+        };
+        let err = execute(deps.as_mut(), mock_env(), mock_info(GENERATOR, &[]), msg).unwrap_err();
+        match err {
+            ContractError::MissingPreviousSignature{} => {},
+            _ => panic!("")
+        }
+    }
+
+    fn request_randomness_paid(deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>, funds: &[Coin]) -> Result<Response, ContractError> {
+        let request_randomness_msg = ExecuteMsg::RequestRandomness {
+            user_seed: Binary::from(USER_SEED),
+            callback_data: Binary::default(),
+            time_set: ROUND_2515680_TIME,
+            num_words: 1,
+            mode: RandomnessMode::Vrf,
+            delivery: Delivery::Callback,
+            expires: None,
+            callback_contract: None,
+            callback_gas_limit: None,
+        };
+
+        execute(deps.as_mut(), mock_env(), mock_info(USER, funds), request_randomness_msg)
+    }
+
+    #[test]
+    fn request_randomness_requires_fee() {
+        let mut deps = setup_with_fee(Some(coin(100, "uatom")));
+        register_generator(&mut deps);
+
+        for funds in [vec![], coins(99, "uatom"), coins(100, "uosmo"), vec![coin(100, "uatom"), coin(1, "uosmo")]] {
+            match request_randomness_paid(&mut deps, &funds).unwrap_err() {
+                ContractError::InvalidRequestFee{expected} => assert_eq!(expected, "100uatom"),
+                _ => panic!("")
+            }
+        }
+
+        request_randomness_paid(&mut deps, &coins(100, "uatom")).unwrap();
+        assert_eq!(RANDOMNESS_REQUESTS.load(&deps.storage, 1).unwrap().escrow, coins(100, "uatom"));
+
+        // free requests take no funds
+        let mut deps = setup();
+        match request_randomness_paid(&mut deps, &coins(100, "uatom")).unwrap_err() {
+            ContractError::InvalidRequestFee{..} => {},
+            _ => panic!("")
+        }
+    }
+
+    #[test]
+    fn push_randomness_releases_fee_to_generator() {
+        let mut deps = setup_with_fee(Some(coin(100, "uatom")));
+        register_generator(&mut deps);
+
+        request_randomness_paid(&mut deps, &coins(100, "uatom")).unwrap();
+        let res = push_randomness(&mut deps, None).unwrap();
+
+        assert_eq!(res.messages.len(), 2);
+        assert_eq!(res.messages[0].msg, CosmosMsg::Bank(BankMsg::Send{
+            to_address: GENERATOR.to_string(),
+            amount: coins(100, "uatom"),
+        }));
+        assert_eq!(res.messages[0].reply_on, ReplyOn::Never);
+        assert_eq!(res.messages[1].reply_on, ReplyOn::Error);
+    }
+
+    #[test]
+    fn cancel_request_refunds_fee() {
+        let mut deps = setup_with_fee(Some(coin(100, "uatom")));
+        register_generator(&mut deps);
+
+        request_randomness_paid(&mut deps, &coins(100, "uatom")).unwrap();
+
+        match execute(deps.as_mut(), mock_env(), mock_info(CREATOR, &[]), ExecuteMsg::CancelRequest{id: 1}).unwrap_err() {
+            ContractError::Unauthorized{} => {},
+            _ => panic!("")
+        }
+
+        let res = execute(deps.as_mut(), mock_env(), mock_info(USER, &[]), ExecuteMsg::CancelRequest{id: 1}).unwrap();
+        assert_eq!(res.messages.len(), 1);
+        assert_eq!(res.messages[0].msg, CosmosMsg::Bank(BankMsg::Send{
+            to_address: USER.to_string(),
+            amount: coins(100, "uatom"),
+        }));
+        assert_eq!(RANDOMNESS_REQUESTS.load(&deps.storage, 1).unwrap().status, RequestStatus::Cancelled);
+
+        // a cancelled request is neither refunded twice nor fulfilled
+        match execute(deps.as_mut(), mock_env(), mock_info(USER, &[]), ExecuteMsg::CancelRequest{id: 1}).unwrap_err() {
+            ContractError::RequestNotPending{id} => assert_eq!(id, 1),
+            _ => panic!("")
+        }
+        match push_randomness(&mut deps, Some(1)).unwrap_err() {
+            ContractError::RequestNotPending{id} => assert_eq!(id, 1),
+            _ => panic!("")
+        }
+    }
+
+    fn request_randomness_expiring(deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>, funds: &[Coin], expires: Option<Expiry>) -> Result<Response, ContractError> {
+        let request_randomness_msg = ExecuteMsg::RequestRandomness {
+            user_seed: Binary::from(USER_SEED),
+            callback_data: Binary::default(),
+            time_set: ROUND_2515680_TIME,
+            num_words: 1,
+            mode: RandomnessMode::Vrf,
+            delivery: Delivery::Callback,
+            expires,
+            callback_contract: None,
+            callback_gas_limit: None,
+        };
+
+        execute(deps.as_mut(), mock_env(), mock_info(USER, funds), request_randomness_msg)
+    }
+
+    #[test]
+    fn expired_request_cannot_be_fulfilled() {
+        let mut deps = setup();
+        register_generator(&mut deps);
+
+        let expires = Expiry::AtTime(Timestamp::from_seconds(ROUND_2515680_TIME as u64 + 10));
+        request_randomness_expiring(&mut deps, &[], Some(expires)).unwrap();
+        request_randomness_expiring(&mut deps, &[], None).unwrap();
+
+        let push = |request_id: Option<u64>, target: u64| ExecuteMsg::PushRandomness {
+            proof: prove_request(target),
+            drand_response: drand_response(),
+            request_id,
+        };
+
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(ROUND_2515680_TIME as u64 + 10);
+        match execute(deps.as_mut(), env.clone(), mock_info(GENERATOR, &[]), push(Some(1), 1)).unwrap_err() {
+            ContractError::RequestExpired{id} => assert_eq!(id, 1),
+            _ => panic!("")
+        }
+
+        // the oldest request is skipped once expired
+        let res = execute(deps.as_mut(), env, mock_info(GENERATOR, &[]), push(None, 2)).unwrap();
+        assert_eq!(res.attributes[1].value, "2");
+    }
+
+    #[test]
+    fn request_randomness_rejects_past_expiry() {
+        let mut deps = setup();
+        register_generator(&mut deps);
+
+        match request_randomness_expiring(&mut deps, &[], Some(Expiry::AtHeight(mock_env().block.height))).unwrap_err() {
+            ContractError::InvalidExpiry{} => {},
+            _ => panic!("")
+        }
+
+        // the request has to stay open until its drand round is due
+        let due = Timestamp::from_seconds(ROUND_2515680_TIME as u64);
+        match request_randomness_expiring(&mut deps, &[], Some(Expiry::AtTime(due))).unwrap_err() {
+            ContractError::InvalidExpiry{} => {},
+            _ => panic!("")
+        }
+        assert_eq!(REQUEST_COUNT.load(&deps.storage).unwrap(), 0);
+    }
+
+    #[test]
+    fn request_timeout_applies_by_default() {
+        let mut deps = setup();
+        register_generator(&mut deps);
+
+        let update_msg = ExecuteMsg::UpdateRequestTimeout{request_timeout: Some(30)};
+        match execute(deps.as_mut(), mock_env(), mock_info(USER, &[]), update_msg.clone()).unwrap_err() {
+            ContractError::Unauthorized{} => {},
+            _ => panic!("")
+        }
+        match execute(deps.as_mut(), mock_env(), mock_info(CREATOR, &[]), ExecuteMsg::UpdateRequestTimeout{request_timeout: Some(0)}).unwrap_err() {
+            ContractError::MustNotBeZero{field} => assert_eq!(field, "request_timeout"),
+            _ => panic!("")
+        }
+        execute(deps.as_mut(), mock_env(), mock_info(CREATOR, &[]), update_msg).unwrap();
+
+        request_randomness_expiring(&mut deps, &[], None).unwrap();
+        request_randomness_expiring(&mut deps, &[], Some(Expiry::AtHeight(mock_env().block.height + 5))).unwrap();
+
+        // the timeout runs from the time the drand round of the request is due
+        assert_eq!(RANDOMNESS_REQUESTS.load(&deps.storage, 1).unwrap().expires,
+            Some(Expiry::AtTime(Timestamp::from_seconds(ROUND_2515680_TIME as u64 + 30))));
+        assert_eq!(RANDOMNESS_REQUESTS.load(&deps.storage, 2).unwrap().expires,
+            Some(Expiry::AtHeight(mock_env().block.height + 5)));
+    }
+
+    #[test]
+    fn sweep_expired_refunds_fees() {
+        let mut deps = setup_with_fee(Some(coin(100, "uatom")));
+        register_generator(&mut deps);
+
+        let now = mock_env().block;
+        let due = Timestamp::from_seconds(ROUND_2515680_TIME as u64);
+        for expires in [Expiry::AtTime(due.plus_seconds(10)), Expiry::AtHeight(now.height + 1), Expiry::AtTime(due.plus_seconds(10))] {
+            request_randomness_expiring(&mut deps, &coins(100, "uatom"), Some(expires)).unwrap();
+        }
+        request_randomness_expiring(&mut deps, &coins(100, "uatom"), None).unwrap();
+        execute(deps.as_mut(), mock_env(), mock_info(USER, &[]), ExecuteMsg::CancelRequest{id: 3}).unwrap();
+
+        // nothing is due yet
+        let res = execute(deps.as_mut(), mock_env(), mock_info(CREATOR, &[]), ExecuteMsg::SweepExpired{limit: None}).unwrap();
+        assert!(res.messages.is_empty());
+
+        let mut env = mock_env();
+        env.block.time = due.plus_seconds(10);
+        env.block.height += 1;
+
+        // expiries by height are swept first
+        let res = execute(deps.as_mut(), env.clone(), mock_info(CREATOR, &[]), ExecuteMsg::SweepExpired{limit: Some(1)}).unwrap();
+        assert_eq!(res.events.len(), 1);
+        assert_eq!(res.events[0].ty, "request_expired");
+        assert_eq!(res.events[0].attributes[0].value, "2");
+        assert_eq!(res.messages[0].msg, CosmosMsg::Bank(BankMsg::Send{
+            to_address: USER.to_string(),
+            amount: coins(100, "uatom"),
+        }));
+
+        // the cancelled request was already refunded
+        let res = execute(deps.as_mut(), env.clone(), mock_info(CREATOR, &[]), ExecuteMsg::SweepExpired{limit: None}).unwrap();
+        assert_eq!(res.messages.len(), 1);
+        assert_eq!(res.events[0].attributes[0].value, "1");
+        assert_eq!(res.attributes[1].value, "1");
+
+        for (id, status) in [(1, RequestStatus::Expired), (2, RequestStatus::Expired), (3, RequestStatus::Cancelled), (4, RequestStatus::Pending)] {
+            assert_eq!(RANDOMNESS_REQUESTS.load(&deps.storage, id).unwrap().status, status);
+        }
+
+        let res = execute(deps.as_mut(), env, mock_info(CREATOR, &[]), ExecuteMsg::SweepExpired{limit: None}).unwrap();
+        assert!(res.messages.is_empty());
+    }
+
+    #[test]
+    fn update_request_fee() {
+        let mut deps = setup();
+        register_generator(&mut deps);
+
+        let update_msg = ExecuteMsg::UpdateRequestFee{request_fee: Some(coin(5, "uatom"))};
+        match execute(deps.as_mut(), mock_env(), mock_info(USER, &[]), update_msg.clone()).unwrap_err() {
+            ContractError::Unauthorized{} => {},
+            _ => panic!("")
+        }
+
+        execute(deps.as_mut(), mock_env(), mock_info(CREATOR, &[]), update_msg).unwrap();
+        assert_eq!(CONFIG.load(&deps.storage).unwrap().request_fee, Some(coin(5, "uatom")));
+
+        // the escrow of earlier requests is kept when the fee changes
+        request_randomness_paid(&mut deps, &coins(5, "uatom")).unwrap();
+        execute(deps.as_mut(), mock_env(), mock_info(CREATOR, &[]), ExecuteMsg::UpdateRequestFee{request_fee: None}).unwrap();
+        assert_eq!(RANDOMNESS_REQUESTS.load(&deps.storage, 1).unwrap().escrow, coins(5, "uatom"));
+
+        let update_msg = ExecuteMsg::UpdateRequestFee{request_fee: Some(coin(0, "uatom"))};
+        match execute(deps.as_mut(), mock_env(), mock_info(CREATOR, &[]), update_msg).unwrap_err() {
+            ContractError::MustNotBeZero{field} => assert_eq!(field, "request_fee"),
+            _ => panic!("")
+        }
+    }
+
+    #[test]
+    fn update_drand_config() {
+        let mut deps = setup();
+
+        let mut drand = leo_mainnet();
+        drand.genesis_time = 1677685200;
+        drand.period = 3;
+
+        let update_msg = ExecuteMsg::UpdateDrandConfig{drand: drand.clone()};
+        let err = execute(deps.as_mut(), mock_env(), mock_info(USER, &[]), update_msg.clone()).unwrap_err();
+        match err {
+            ContractError::Unauthorized{} => {},
+            _ => panic!("")
+        }
+
+        execute(deps.as_mut(), mock_env(), mock_info(CREATOR, &[]), update_msg).unwrap();
+
+        let config: Config = from_json(query(deps.as_ref(), mock_env(), QueryMsg::Config{}).unwrap()).unwrap();
+        assert_eq!(config.drand, drand);
+    }
+
+    fn request_commit_reveal(deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>, funds: &[Coin]) -> u64 {
+        let request_randomness_msg = ExecuteMsg::RequestRandomness {
+            user_seed: Binary::from(USER_SEED),
+            callback_data: Binary::default(),
+            time_set: ROUND_2515680_TIME,
+            num_words: 1,
+            mode: RandomnessMode::CommitReveal,
+            delivery: Delivery::Callback,
+            expires: None,
+            callback_contract: None,
+            callback_gas_limit: None,
+        };
+
+        let res = execute(deps.as_mut(), mock_env(), mock_info(USER, funds), request_randomness_msg).unwrap();
+        let data: RequestRandomnessResponse = from_json(res.data.unwrap()).unwrap();
+        data.request_id
+    }
+
+    /// Registers `generators`, each with its own VRF key, and commits each of them to
+    /// `request_id` with its own secret
+    fn commit_generators(deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>, request_id: u64, generators: &[&str]) {
+        for generator in generators {
+            let secret_key: [u8; 32] = sha256_hash(generator.as_bytes()).try_into().unwrap();
+            let register_msg = ExecuteMsg::Register{public_key: ecvrf::prover::public_key(&secret_key)};
+            execute(deps.as_mut(), mock_env(), mock_info(generator, &stake()), register_msg).unwrap();
+
+            let commit_msg = ExecuteMsg::Commit {
+                request_id,
+                commitment: hex::encode(sha256_hash(generator.as_bytes())),
+            };
+            execute(deps.as_mut(), mock_env(), mock_info(generator, &[]), commit_msg).unwrap();
+        }
+    }
+
+    fn env_at(seconds_after_request: u64) -> Env {
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(seconds_after_request);
+        env
+    }
+
+    #[test]
+    fn commit_reveal_mixes_reveals_and_slashes_withheld() {
+        let mut deps = setup_with_fee(Some(coin(101, "uatom")));
+        let id = request_commit_reveal(&mut deps, &coins(101, "uatom"));
+        commit_generators(&mut deps, id, &["alice", "bob", "carol"]);
+
+        // commitments are closed after the commit phase
+        let commit_msg = ExecuteMsg::Commit{request_id: id, commitment: hex::encode([0u8; 32])};
+        execute(deps.as_mut(), mock_env(), mock_info(GENERATOR, &stake()), ExecuteMsg::Register{public_key: GENERATOR_PUBLIC_KEY.to_string()}).unwrap();
+        match execute(deps.as_mut(), env_at(COMMIT_PERIOD), mock_info(GENERATOR, &[]), commit_msg).unwrap_err() {
+            ContractError::WrongCommitRevealPhase{phase, ..} => assert_eq!(phase, "commit"),
+            _ => panic!("")
+        }
+
+        // secrets are not revealed during the commit phase
+        let reveal_msg = |generator: &str| ExecuteMsg::Reveal{request_id: id, secret: hex::encode(generator)};
+        match execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), reveal_msg("alice")).unwrap_err() {
+            ContractError::WrongCommitRevealPhase{phase, ..} => assert_eq!(phase, "reveal"),
+            _ => panic!("")
+        }
+        match execute(deps.as_mut(), env_at(COMMIT_PERIOD), mock_info("alice", &[]), reveal_msg("bob")).unwrap_err() {
+            ContractError::CommitmentMismatch{id: mismatch} => assert_eq!(mismatch, id),
+            _ => panic!("")
+        }
+
+        // carol withholds her secret
+        execute(deps.as_mut(), env_at(COMMIT_PERIOD), mock_info("alice", &[]), reveal_msg("alice")).unwrap();
+        execute(deps.as_mut(), env_at(COMMIT_PERIOD), mock_info("bob", &[]), reveal_msg("bob")).unwrap();
+
+        // a single generator cannot fulfill the request
+        let push_randomness_msg = ExecuteMsg::PushRandomness {
+            proof: prove_request(id),
+            drand_response: drand_response(),
+            request_id: Some(id),
+        };
+        match execute(deps.as_mut(), env_at(COMMIT_PERIOD), mock_info(GENERATOR, &[]), push_randomness_msg).unwrap_err() {
+            ContractError::WrongRandomnessMode{..} => {},
+            _ => panic!("")
+        }
+
+        let finalize_msg = ExecuteMsg::FinalizeCommitReveal{request_id: id, drand_response: drand_response()};
+        match execute(deps.as_mut(), env_at(COMMIT_PERIOD), mock_info(USER, &[]), finalize_msg.clone()).unwrap_err() {
+            ContractError::WrongCommitRevealPhase{phase, ..} => assert_eq!(phase, "finalize"),
+            _ => panic!("")
+        }
+
+        let res = execute(deps.as_mut(), env_at(COMMIT_PERIOD + REVEAL_PERIOD), mock_info(USER, &[]), finalize_msg).unwrap();
+        assert_eq!(res.messages[..3], vec![
+            SubMsg::new(BankMsg::Send{to_address: "alice".to_string(), amount: coins(51, "uatom")}),
+            SubMsg::new(BankMsg::Send{to_address: "bob".to_string(), amount: coins(50, "uatom")}),
+            SubMsg::new(BankMsg::Send{to_address: TREASURY.to_string(), amount: coins(100, "ustake")}),
+        ]);
+        assert_eq!(res.messages[3].reply_on, ReplyOn::Error);
+
+        let request: RandomnessRequest = from_json(query(deps.as_ref(), mock_env(), QueryMsg::Request{id}).unwrap()).unwrap();
+        assert_eq!(request.status, RequestStatus::Fulfilled);
+
+        // the output depends on every reveal and the drand beacon
+        let drand_randomness = derive_randomness_from_signature(&hex::decode(ROUND_SIGNATURE).unwrap());
+        let mut seed = COMMIT_REVEAL_DOMAIN.to_vec();
+        seed.extend(randomness_preimage(&mock_env(), id, ROUND, &drand_randomness));
+        for generator in ["alice", "bob"] {
+            extend_length_prefixed(&mut seed, generator.as_bytes());
+            extend_length_prefixed(&mut seed, generator.as_bytes());
+        }
+        let result: FulfilledResult = from_json(query(deps.as_ref(), mock_env(), QueryMsg::FulfilledResult{id}).unwrap()).unwrap();
+        assert_eq!(result.randomness, hex::encode(derive_output(&sha256_hash(&seed), USER_SEED)));
+        assert_eq!(result.generator, None);
+        assert_eq!(request.assigned_generator, None);
+
+        // carol fell below the minimum stake
+        query(deps.as_ref(), mock_env(), QueryMsg::Generator{addr: "carol".to_string()}).unwrap_err();
+        let unbonding: Unbonding = from_json(query(deps.as_ref(), mock_env(),
+            QueryMsg::Unbonding{addr: "carol".to_string()}).unwrap()).unwrap();
+        assert_eq!(unbonding.amount, Uint128::new(900));
+    }
+
+    #[test]
+    fn cancel_is_refused_once_the_result_can_be_known() {
+        let mut deps = setup();
+        register_generator(&mut deps);
+
+        // the drand round of a VRF request is out
+        let id = request_randomness(&mut deps, USER);
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(ROUND_2515680_TIME as u64);
+        match execute(deps.as_mut(), env.clone(), mock_info(USER, &[]), ExecuteMsg::CancelRequest{id}).unwrap_err() {
+            ContractError::RequestNotCancellable{id: refused} => assert_eq!(refused, id),
+            _ => panic!("")
+        }
+
+        // a threshold request is due, the committee may already have signed it
+        let register_msg = ExecuteMsg::RegisterCommittee {
+            public_key: crate::drand_verify::signer::public_key(committee_secret_key()),
+            members: vec!["alice".to_string()],
+            threshold: 1,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info(CREATOR, &[]), register_msg).unwrap();
+        let id = from_json::<RequestRandomnessResponse>(request_threshold(&mut deps, &[]).unwrap().data.unwrap()).unwrap().request_id;
+        match execute(deps.as_mut(), env, mock_info(USER, &[]), ExecuteMsg::CancelRequest{id}).unwrap_err() {
+            ContractError::RequestNotCancellable{..} => {},
+            _ => panic!("")
+        }
+        execute(deps.as_mut(), mock_env(), mock_info(USER, &[]), ExecuteMsg::CancelRequest{id}).unwrap();
+
+        // a generator committed to a commit-reveal request
+        let id = request_commit_reveal(&mut deps, &[]);
+        commit_generators(&mut deps, id, &["alice"]);
+        match execute(deps.as_mut(), mock_env(), mock_info(USER, &[]), ExecuteMsg::CancelRequest{id}).unwrap_err() {
+            ContractError::RequestNotCancellable{..} => {},
+            _ => panic!("")
+        }
+
+        // the commit phase of a commit-reveal request is over
+        let id = request_commit_reveal(&mut deps, &[]);
+        match execute(deps.as_mut(), env_at(COMMIT_PERIOD), mock_info(USER, &[]), ExecuteMsg::CancelRequest{id}).unwrap_err() {
+            ContractError::RequestNotCancellable{..} => {},
+            _ => panic!("")
+        }
+        execute(deps.as_mut(), mock_env(), mock_info(USER, &[]), ExecuteMsg::CancelRequest{id}).unwrap();
+    }
+
+    #[test]
+    fn commit_reveal_limits_committers() {
+        let mut deps = setup();
+        let id = request_commit_reveal(&mut deps, &[]);
+        commit_generators(&mut deps, id, &["alice", "bob", "carol"]);
+
+        register_generator(&mut deps);
+        let commit_msg = ExecuteMsg::Commit{request_id: id, commitment: hex::encode([0u8; 32])};
+        match execute(deps.as_mut(), mock_env(), mock_info(GENERATOR, &[]), commit_msg).unwrap_err() {
+            ContractError::TooManyCommitters{id: full, max} => assert_eq!((full, max), (id, 3)),
+            _ => panic!("")
+        }
+
+        // the cap cannot rule out the minimum reveals
+        let update_msg = ExecuteMsg::UpdateCommitRevealConfig{commit_reveal: CommitRevealConfig{max_committers: 1, ..commit_reveal()}};
+        match execute(deps.as_mut(), mock_env(), mock_info(CREATOR, &[]), update_msg).unwrap_err() {
+            ContractError::InvalidMaxCommitters{} => {},
+            _ => panic!("")
+        }
+
+        let update_msg = ExecuteMsg::UpdateCommitRevealConfig{commit_reveal: CommitRevealConfig{reveal_period: 0, ..commit_reveal()}};
+        match execute(deps.as_mut(), mock_env(), mock_info(CREATOR, &[]), update_msg).unwrap_err() {
+            ContractError::MustNotBeZero{field} => assert_eq!(field, "reveal_period"),
+            _ => panic!("")
+        }
+    }
+
+    #[test]
+    fn commit_reveal_without_enough_reveals_is_cancelled() {
+        let mut deps = setup_with_fee(Some(coin(100, "uatom")));
+        let id = request_commit_reveal(&mut deps, &coins(100, "uatom"));
+        commit_generators(&mut deps, id, &["alice", "bob"]);
+
+        // bob leaves instead of revealing
+        let reveal_msg = ExecuteMsg::Reveal{request_id: id, secret: hex::encode("alice")};
+        execute(deps.as_mut(), env_at(COMMIT_PERIOD), mock_info("alice", &[]), reveal_msg).unwrap();
+        execute(deps.as_mut(), env_at(COMMIT_PERIOD), mock_info("bob", &[]), ExecuteMsg::DeleteGenerator{}).unwrap();
+
+        let finalize_msg = ExecuteMsg::FinalizeCommitReveal{request_id: id, drand_response: drand_response()};
+        let res = execute(deps.as_mut(), env_at(COMMIT_PERIOD + REVEAL_PERIOD), mock_info(USER, &[]), finalize_msg).unwrap();
+        assert_eq!(res.messages, vec![
+            SubMsg::new(BankMsg::Send{to_address: TREASURY.to_string(), amount: coins(100, "ustake")}),
+            SubMsg::new(BankMsg::Send{to_address: USER.to_string(), amount: coins(100, "uatom")}),
+        ]);
+
+        let request: RandomnessRequest = from_json(query(deps.as_ref(), mock_env(), QueryMsg::Request{id}).unwrap()).unwrap();
+        assert_eq!(request.status, RequestStatus::Cancelled);
+
+        // leaving did not save bob's stake
+        let unbonding: Unbonding = from_json(query(deps.as_ref(), mock_env(),
+            QueryMsg::Unbonding{addr: "bob".to_string()}).unwrap()).unwrap();
+        assert_eq!(unbonding.amount, Uint128::new(900));
+
+        let res: CommitmentsResponse = from_json(query(deps.as_ref(), mock_env(), QueryMsg::Commitments{request_id: id}).unwrap()).unwrap();
+        assert_eq!(res.commitments.len(), 2);
+        assert_eq!(res.commitments[0].secret, Some(hex::encode("alice")));
+        assert_eq!(res.commitments[1].secret, None);
+    }
+
+    fn committee_secret_key() -> paired::bls12_381::Fr {
+        use fff::PrimeField;
+        paired::bls12_381::Fr::from_repr(paired::bls12_381::FrRepr::from(42)).unwrap()
+    }
+
+    fn request_threshold(deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>, funds: &[Coin]) -> Result<Response, ContractError> {
+        let request_randomness_msg = ExecuteMsg::RequestRandomness {
+            user_seed: Binary::from(USER_SEED),
+            callback_data: Binary::default(),
+            time_set: ROUND_2515680_TIME,
+            num_words: 1,
+            mode: RandomnessMode::Threshold,
+            delivery: Delivery::Callback,
+            expires: None,
+            callback_contract: None,
+            callback_gas_limit: None,
+        };
+
+        execute(deps.as_mut(), mock_env(), mock_info(USER, funds), request_randomness_msg)
+    }
+
+    #[test]
+    fn threshold_request_is_fulfilled_by_group_signature() {
+        let mut deps = setup_with_fee(Some(coin(101, "uatom")));
+
+        match request_threshold(&mut deps, &coins(101, "uatom")).unwrap_err() {
+            ContractError::NoCommittee{} => {},
+            _ => panic!("")
+        }
+
+        let register_msg = ExecuteMsg::RegisterCommittee {
+            public_key: crate::drand_verify::signer::public_key(committee_secret_key()),
+            members: vec!["alice".to_string(), "bob".to_string()],
+            threshold: 2,
+        };
+        match execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), register_msg.clone()).unwrap_err() {
+            ContractError::Unauthorized{} => {},
+            _ => panic!("")
+        }
+        let too_high = ExecuteMsg::RegisterCommittee {
+            public_key: crate::drand_verify::signer::public_key(committee_secret_key()),
+            members: vec!["alice".to_string(), "bob".to_string()],
+            threshold: 3,
+        };
+        match execute(deps.as_mut(), mock_env(), mock_info(CREATOR, &[]), too_high).unwrap_err() {
+            ContractError::InvalidCommitteeThreshold{members} => assert_eq!(members, 2),
+            _ => panic!("")
+        }
+        execute(deps.as_mut(), mock_env(), mock_info(CREATOR, &[]), register_msg).unwrap();
+
+        let committee: Committee = from_json(query(deps.as_ref(), mock_env(), QueryMsg::Committee{}).unwrap()).unwrap();
+        assert_eq!(committee.members, vec![Addr::unchecked("alice"), Addr::unchecked("bob")]);
+        assert_eq!(committee.threshold, 2);
+
+        let res = request_threshold(&mut deps, &coins(101, "uatom")).unwrap();
+        let id = from_json::<RequestRandomnessResponse>(res.data.unwrap()).unwrap().request_id;
+        let request: RandomnessRequest = from_json(query(deps.as_ref(), mock_env(), QueryMsg::Request{id}).unwrap()).unwrap();
+        assert_eq!(request.min_round, 2515680);
+        assert_eq!(request.assigned_generator, None);
+
+        let signature = crate::drand_verify::signer::sign(committee_secret_key(), &threshold_message(&mock_env(), id, 2515680));
+        let push_msg = |signature: String| ExecuteMsg::PushThresholdSignature{request_id: id, signature};
+
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(ROUND_2515680_TIME as u64 - 1);
+        match execute(deps.as_mut(), env.clone(), mock_info("anyone", &[]), push_msg(signature.clone())).unwrap_err() {
+            ContractError::RequestNotDue{time, ..} => assert_eq!(u128::from(time), ROUND_2515680_TIME),
+            _ => panic!("")
+        }
+
+        // signatures of another request or of another round
+        env.block.time = env.block.time.plus_seconds(1);
+        for (other_id, other_round) in [(id + 1, 2515680), (id, 2515681)] {
+            let other = crate::drand_verify::signer::sign(committee_secret_key(), &threshold_message(&mock_env(), other_id, other_round));
+            match execute(deps.as_mut(), env.clone(), mock_info("anyone", &[]), push_msg(other)).unwrap_err() {
+                ContractError::InvalidGroupSignature{} => {},
+                _ => panic!("")
+            }
+        }
+
+        let res = execute(deps.as_mut(), env, mock_info("anyone", &[]), push_msg(signature.clone())).unwrap();
+        assert_eq!(res.messages[..2], vec![
+            SubMsg::new(BankMsg::Send{to_address: "alice".to_string(), amount: coins(51, "uatom")}),
+            SubMsg::new(BankMsg::Send{to_address: "bob".to_string(), amount: coins(50, "uatom")}),
+        ]);
+        assert_eq!(res.messages[2].reply_on, ReplyOn::Error);
+
+        let result: FulfilledResult = from_json(query(deps.as_ref(), mock_env(), QueryMsg::FulfilledResult{id}).unwrap()).unwrap();
+        assert_eq!(result.round, 2515680);
+        assert_eq!(result.generator, None);
+        assert_eq!(result.proof, signature);
+        assert_eq!(result.randomness, hex::encode(derive_output(&hex::decode(&signature).unwrap(), USER_SEED)));
+
+        // threshold requests are neither queued for nor fulfilled by VRF pushes
+        register_generator(&mut deps);
+        let vrf_id = from_json::<RequestRandomnessResponse>(request_randomness_paid(&mut deps, &coins(101, "uatom")).unwrap().data.unwrap()).unwrap().request_id;
+        let threshold_id = from_json::<RequestRandomnessResponse>(request_threshold(&mut deps, &coins(101, "uatom")).unwrap().data.unwrap()).unwrap().request_id;
+        push_randomness(&mut deps, None).unwrap();
+        let request: RandomnessRequest = from_json(query(deps.as_ref(), mock_env(), QueryMsg::Request{id: vrf_id}).unwrap()).unwrap();
+        assert_eq!(request.status, RequestStatus::Fulfilled);
+        match push_randomness(&mut deps, Some(threshold_id)).unwrap_err() {
+            ContractError::WrongRandomnessMode{..} => {},
+            _ => panic!("")
+        }
+    }
+}
+
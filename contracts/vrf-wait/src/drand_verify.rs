@@ -2,26 +2,354 @@ mod points;
 mod randomness;
 mod verify;
 
-use points::{
+pub use points::{
     g1_from_fixed, g1_from_fixed_unchecked, g1_from_variable, g1_from_variable_unchecked,
     g2_from_fixed, g2_from_fixed_unchecked, g2_from_variable, g2_from_variable_unchecked,
 };
-use randomness::derive_randomness;
-use verify::{verify, verify_step1, verify_step2, VerificationError};
-use hex_literal::hex;
+pub use randomness::derive_randomness;
+pub use verify::{verify, verify_g1_signature, verify_message, verify_step1, verify_step2, verify_unchained, VerificationError};
+use paired::bls12_381::{G1Affine, G2Affine};
+use thiserror::Error;
 
-/// Public key League of Entropy Mainnet (curl -sS https://drand.cloudflare.com/info)
-const PK_LEO_MAINNET: [u8; 48] = hex!("868f005eb8e6e4ca0a47c8a77ceaa5309a47978a7c71bc5cce96366b5d7a569937c529eeda66c7293784a9402801af31");
+use crate::state::DrandScheme;
 
-pub fn verify_drand_randomness(round: u64, signature: Vec<u8>, previous_signature: Vec<u8>) -> bool{
-    let pk = g1_from_fixed(PK_LEO_MAINNET).unwrap();
+/// Length of a compressed G1 point
+const G1_POINT_LENGTH: usize = 48;
+/// Length of a compressed G2 point
+const G2_POINT_LENGTH: usize = 96;
 
-    match verify(&pk, round, &previous_signature, &signature) {
-        Err(err) => return false,
-        Ok(valid) => return true 
+#[derive(Error, Debug, PartialEq)]
+pub enum DrandError {
+    #[error("Invalid hex in {field}")]
+    InvalidHex { field: String },
+
+    #[error("Invalid length of {field}: expected {expected} bytes, got {actual}")]
+    InvalidLength { field: String, expected: usize, actual: usize },
+
+    #[error("Invalid curve point for {field}: {msg}")]
+    InvalidPoint { field: String, msg: String },
+
+    #[error("Drand pairing check failed")]
+    VerificationFailed {},
+
+    #[error("Previous signature is required by chained drand schemes")]
+    MissingPreviousSignature {},
+}
+
+/// Public key of a drand network, on G1 or G2 depending on the scheme
+pub enum DrandPublicKey {
+    G1(G1Affine),
+    G2(G2Affine),
+}
+
+fn decode_fixed_hex(field: &str, value: &str, expected: usize) -> Result<Vec<u8>, DrandError> {
+    let bytes = hex::decode(value).map_err(|_| DrandError::InvalidHex{field: field.to_string()})?;
+
+    if bytes.len() != expected {
+        return Err(DrandError::InvalidLength{
+            field: field.to_string(),
+            expected,
+            actual: bytes.len(),
+        });
     }
+
+    Ok(bytes)
+}
+
+fn invalid_public_key(err: impl ToString) -> DrandError {
+    DrandError::InvalidPoint{
+        field: "public_key".to_string(),
+        msg: err.to_string(),
+    }
+}
+
+/// Decodes a hex encoded drand network public key of the given scheme
+pub fn decode_public_key(scheme: &DrandScheme, public_key: &str) -> Result<DrandPublicKey, DrandError> {
+    if scheme.signs_on_g1() {
+        let bytes = decode_fixed_hex("public_key", public_key, G2_POINT_LENGTH)?;
+        g2_from_variable(&bytes).map(DrandPublicKey::G2).map_err(invalid_public_key)
+    } else {
+        let bytes = decode_fixed_hex("public_key", public_key, G1_POINT_LENGTH)?;
+        g1_from_variable(&bytes).map(DrandPublicKey::G1).map_err(invalid_public_key)
+    }
+}
+
+/// Verifies a hex encoded drand beacon against the hex encoded network public key.
+/// `previous_signature` is only used, and required, by the chained scheme.
+pub fn verify_drand_randomness(
+    scheme: &DrandScheme,
+    public_key: &str,
+    round: u64,
+    signature: &str,
+    previous_signature: Option<&str>,
+) -> Result<(), DrandError> {
+    let result = match decode_public_key(scheme, public_key)? {
+        DrandPublicKey::G1(pk) => {
+            let signature = decode_fixed_hex("signature", signature, G2_POINT_LENGTH)?;
+            if scheme.is_chained() {
+                let previous_signature = previous_signature.ok_or(DrandError::MissingPreviousSignature{})?;
+                let previous_signature = decode_fixed_hex("previous_signature", previous_signature, G2_POINT_LENGTH)?;
+                verify(&pk, round, &previous_signature, &signature)
+            } else {
+                verify_unchained(&pk, round, &signature)
+            }
+        }
+        DrandPublicKey::G2(pk) => {
+            let signature = decode_fixed_hex("signature", signature, G1_POINT_LENGTH)?;
+            verify_g1_signature(&pk, round, &signature, *scheme == DrandScheme::BlsUnchainedG1Rfc9380)
+        }
+    };
+
+    verification_result(result)
+}
+
+fn verification_result(result: Result<bool, VerificationError>) -> Result<(), DrandError> {
+    match result {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(DrandError::VerificationFailed{}),
+        Err(VerificationError::InvalidPoint{field, msg}) => Err(DrandError::InvalidPoint{field, msg}),
+    }
+}
+
+/// Decodes a hex encoded BLS public key on G1, such as the group key of a threshold committee
+pub fn decode_g1_public_key(public_key: &str) -> Result<G1Affine, DrandError> {
+    let bytes = decode_fixed_hex("public_key", public_key, G1_POINT_LENGTH)?;
+    g1_from_variable(&bytes).map_err(invalid_public_key)
+}
+
+/// Verifies a hex encoded BLS signature on G2 of `message` under a hex encoded public key on G1
+pub fn verify_bls_signature(public_key: &str, message: &[u8], signature: &str) -> Result<(), DrandError> {
+    let pk = decode_g1_public_key(public_key)?;
+    let signature = decode_fixed_hex("signature", signature, G2_POINT_LENGTH)?;
+    verification_result(verify_message(&pk, message, &signature))
 }
 
 pub fn derive_randomness_from_signature(signature: &[u8]) -> Vec<u8>{
-    return derive_randomness(signature).to_vec();
-}
\ No newline at end of file
+    derive_randomness(signature).to_vec()
+}
+
+/// First drand round emitted at or after `time`, all times in unix seconds
+pub fn round_after(genesis_time: u64, period: u64, time: u64) -> u64 {
+    if time <= genesis_time {
+        return 1;
+    }
+
+    (time - genesis_time).div_ceil(period) + 1
+}
+
+/// Unix time in seconds at which `round` is emitted
+pub fn round_time(genesis_time: u64, period: u64, round: u64) -> u64 {
+    genesis_time + round.saturating_sub(1) * period
+}
+
+/// BLS signing with keys on G1, standing in for a threshold committee in tests
+#[cfg(test)]
+pub mod signer {
+    use groupy::{CurveAffine, CurveProjective};
+    use paired::bls12_381::{Fr, G1Affine, G2};
+
+    use super::verify::msg_to_curve;
+
+    pub fn public_key(secret_key: Fr) -> String {
+        hex::encode(G1Affine::one().mul(secret_key).into_affine().into_compressed())
+    }
+
+    pub fn sign_point(secret_key: Fr, message: &[u8]) -> G2 {
+        msg_to_curve(message).mul(secret_key)
+    }
+
+    pub fn sign(secret_key: Fr, message: &[u8]) -> String {
+        hex::encode(sign_point(secret_key, message).into_affine().into_compressed())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHAINED: DrandScheme = DrandScheme::PedersenBlsChained;
+
+    /// Public key League of Entropy Mainnet (curl -sS https://drand.cloudflare.com/info)
+    const PK_LEO_MAINNET: &str = "868f005eb8e6e4ca0a47c8a77ceaa5309a47978a7c71bc5cce96366b5d7a569937c529eeda66c7293784a9402801af31";
+
+    // curl -sS https://drand.cloudflare.com/public/72785
+    const ROUND: u64 = 72785;
+    const SIGNATURE: &str = "82f5d3d2de4db19d40a6980e8aa37842a0e55d1df06bd68bddc8d60002e8e959eb9cfa368b3c1b77d18f02a54fe047b80f0989315f83b12a74fd8679c4f12aae86eaf6ab5690b34f1fddd50ee3cc6f6cdf59e95526d5a5d82aaa84fa6f181e42";
+    const PREVIOUS_SIGNATURE: &str = "a609e19a03c2fcc559e8dae14900aaefe517cb55c840f6e69bc8e4f66c8d18e8a609685d9917efbfb0c37f058c2de88f13d297c7e19e0ab24813079efe57a182554ff054c7638153f9b26a60e7111f71a0ff63d9571704905d3ca6df0b031747";
+
+    #[test]
+    fn verify_drand_randomness_works() {
+        assert_eq!(verify_drand_randomness(&CHAINED, PK_LEO_MAINNET, ROUND, SIGNATURE, Some(PREVIOUS_SIGNATURE)), Ok(()));
+    }
+
+    #[test]
+    fn verify_drand_randomness_rejects_wrong_beacon() {
+        assert_eq!(verify_drand_randomness(&CHAINED, PK_LEO_MAINNET, 321, SIGNATURE, Some(PREVIOUS_SIGNATURE)),
+            Err(DrandError::VerificationFailed{}));
+
+        // signature of https://drand.cloudflare.com/public/1 is a valid point for another round
+        let wrong_signature = "8d61d9100567de44682506aea1a7a6fa6e5491cd27a0a0ed349ef6910ac5ac20ff7bc3e09d7c046566c9f7f3c6f3b10104990e7cb424998203d8f7de586fb7fa5f60045417a432684f85093b06ca91c769f0e7ca19268375e659c2a2352b4655";
+        assert_eq!(verify_drand_randomness(&CHAINED, PK_LEO_MAINNET, ROUND, wrong_signature, Some(PREVIOUS_SIGNATURE)),
+            Err(DrandError::VerificationFailed{}));
+    }
+
+    #[test]
+    fn verify_drand_randomness_rejects_malformed_input() {
+        assert_eq!(verify_drand_randomness(&CHAINED, PK_LEO_MAINNET, ROUND, "zz", Some(PREVIOUS_SIGNATURE)),
+            Err(DrandError::InvalidHex{field: "signature".to_string()}));
+
+        assert_eq!(verify_drand_randomness(&CHAINED, PK_LEO_MAINNET, ROUND, SIGNATURE, Some("aabb")),
+            Err(DrandError::InvalidLength{field: "previous_signature".to_string(), expected: 96, actual: 2}));
+
+        let not_on_curve = format!("a0{}", &SIGNATURE[2..]);
+        match verify_drand_randomness(&CHAINED, PK_LEO_MAINNET, ROUND, &not_on_curve, Some(PREVIOUS_SIGNATURE)) {
+            Err(DrandError::InvalidPoint{field, ..}) => assert_eq!(field, "signature"),
+            other => panic!("unexpected result {:?}", other),
+        }
+
+        assert_eq!(verify_drand_randomness(&CHAINED, "aabb", ROUND, SIGNATURE, Some(PREVIOUS_SIGNATURE)),
+            Err(DrandError::InvalidLength{field: "public_key".to_string(), expected: 48, actual: 2}));
+    }
+
+    #[test]
+    fn verify_drand_randomness_requires_previous_signature_when_chained() {
+        assert_eq!(verify_drand_randomness(&CHAINED, PK_LEO_MAINNET, ROUND, SIGNATURE, None),
+            Err(DrandError::MissingPreviousSignature{}));
+    }
+
+    /// Public key of the unchained League of Entropy Testnet
+    const PK_UNCHAINED_TESTNET: &str = "8200fc249deb0148eb918d6e213980c5d01acd7fc251900d9260136da3b54836ce125172399ddc69c4e3e11429b62c11";
+
+    #[test]
+    fn verify_drand_randomness_works_unchained() {
+        let scheme = DrandScheme::PedersenBlsUnchained;
+        // https://pl-us.testnet.drand.sh/7672797f548f3f4748ac4bf3352fc6c6b6468c9ad40ad456a397545c6e2df5bf/public/223344
+        let signature = "94f6b85df7cce7237e8e7df66d794ddad092de5d8bb6a791b97e905aa89852e506ac36a792eba7021e22eebf34891f8914bf9a8dd9233ea0a4c5ca00ef8404999f899073dd2eade61fe54077fee8168f83dcb61a758b6883b38904054e64a433";
+        assert_eq!(verify_drand_randomness(&scheme, PK_UNCHAINED_TESTNET, 223344, signature, None), Ok(()));
+
+        // a previous signature is ignored
+        assert_eq!(verify_drand_randomness(&scheme, PK_UNCHAINED_TESTNET, 223344, signature, Some("aabb")), Ok(()));
+
+        assert_eq!(verify_drand_randomness(&scheme, PK_UNCHAINED_TESTNET, 223345, signature, None),
+            Err(DrandError::VerificationFailed{}));
+    }
+
+    /// Public key of fastnet (https://api.drand.sh/dbd506d6ef76e5f386f41c651dcb808c5bcbd75471cc4eafa3f4df7ad4e4c493/info)
+    const PK_FASTNET: &str = "a0b862a7527fee3a731bcb59280ab6abd62d5c0b6ea03dc4ddf6612fdfc9d01f01c31542541771903475eb1ec6615f8d0df0b8b6dce385811d6dcf8cbefb8759e5e616a3dfd054c928940766d9a5b9db91e3b697e5d70a975181e007f87fca5e";
+
+    #[test]
+    fn verify_drand_randomness_works_unchained_on_g1() {
+        let scheme = DrandScheme::BlsUnchainedOnG1;
+        let round_1 = "9544ddce2fdbe8688d6f5b4f98eed5d63eee3902e7e162050ac0f45905a55657714880adabe3c3096b92767d886567d0";
+        let round_23456 = "98401ef9833e75bf06fda3243e4fcf6d075d62b45c2a59d26df5d5fcbdfd0c14ee89fc035abd5528a8c25b68fbecae65";
+
+        assert_eq!(verify_drand_randomness(&scheme, PK_FASTNET, 1, round_1, None), Ok(()));
+        assert_eq!(verify_drand_randomness(&scheme, PK_FASTNET, 23456, round_23456, None), Ok(()));
+        assert_eq!(verify_drand_randomness(&scheme, PK_FASTNET, 23456, round_1, None),
+            Err(DrandError::VerificationFailed{}));
+
+        // signatures on G2 are rejected
+        assert_eq!(verify_drand_randomness(&scheme, PK_FASTNET, ROUND, SIGNATURE, None),
+            Err(DrandError::InvalidLength{field: "signature".to_string(), expected: 48, actual: 96}));
+    }
+
+    #[test]
+    fn verify_drand_randomness_works_quicknet() {
+        // https://api.drand.sh/52db9ba70e0cc0f6eaf7803dd07447a1f5477735fd3f661792ba94600c84e971/info
+        let pk_quicknet = "83cf0f2896adee7eb8b5f01fcad3912212c437e0073e911fb90022d3e760183c8c4b450b6a0a6c3ac6a5776a2d1064510d1fec758c921cc22b0e17e63aaf4bcb5ed66304de9cf809bd274ca73bab4af5a6e9c76a4bc09e76eae8991ef5ece45a";
+        let round_123 = "b75c69d0b72a5d906e854e808ba7e2accb1542ac355ae486d591aa9d43765482e26cd02df835d3546d23c4b13e0dfc92";
+
+        assert_eq!(verify_drand_randomness(&DrandScheme::BlsUnchainedG1Rfc9380, pk_quicknet, 123, round_123, None), Ok(()));
+        assert_eq!(verify_drand_randomness(&DrandScheme::BlsUnchainedG1Rfc9380, pk_quicknet, 124, round_123, None),
+            Err(DrandError::VerificationFailed{}));
+
+        // the domain separation tag differs from bls-unchained-on-g1
+        assert_eq!(verify_drand_randomness(&DrandScheme::BlsUnchainedOnG1, pk_quicknet, 123, round_123, None),
+            Err(DrandError::VerificationFailed{}));
+    }
+
+    #[test]
+    fn decode_public_key_checks_scheme_group() {
+        assert!(decode_public_key(&DrandScheme::BlsUnchainedOnG1, PK_FASTNET).is_ok());
+        assert!(matches!(decode_public_key(&CHAINED, PK_FASTNET),
+            Err(DrandError::InvalidLength{expected: 48, actual: 96, ..})));
+        assert!(matches!(decode_public_key(&DrandScheme::BlsUnchainedOnG1, PK_LEO_MAINNET),
+            Err(DrandError::InvalidLength{expected: 96, actual: 48, ..})));
+    }
+
+    #[test]
+    fn round_after_works() {
+        // League of Entropy Mainnet
+        let (genesis_time, period) = (1595431050, 30);
+
+        assert_eq!(round_after(genesis_time, period, 0), 1);
+        assert_eq!(round_after(genesis_time, period, genesis_time), 1);
+        assert_eq!(round_after(genesis_time, period, genesis_time + 1), 2);
+        assert_eq!(round_after(genesis_time, period, genesis_time + 30), 2);
+        assert_eq!(round_after(genesis_time, period, genesis_time + 31), 3);
+
+        // round 2515680 (curl -sS https://drand.cloudflare.com/public/2515680) is due at 1670901420
+        assert_eq!(round_time(genesis_time, period, 1), genesis_time);
+        assert_eq!(round_time(genesis_time, period, 2515680), 1670901420);
+        assert_eq!(round_after(genesis_time, period, round_time(genesis_time, period, 2515680)), 2515680);
+    }
+
+    fn scalar(value: u64) -> paired::bls12_381::Fr {
+        use fff::PrimeField;
+        paired::bls12_381::Fr::from_repr(paired::bls12_381::FrRepr::from(value)).unwrap()
+    }
+
+    #[test]
+    fn verify_bls_signature_works() {
+        let secret_key = scalar(42);
+        let public_key = signer::public_key(secret_key);
+        let signature = signer::sign(secret_key, b"message");
+
+        assert_eq!(verify_bls_signature(&public_key, b"message", &signature), Ok(()));
+        assert_eq!(verify_bls_signature(&public_key, b"other message", &signature), Err(DrandError::VerificationFailed{}));
+        assert!(matches!(verify_bls_signature(&public_key, b"message", &signature[2..]),
+            Err(DrandError::InvalidHex{..}) | Err(DrandError::InvalidLength{..})));
+    }
+
+    #[test]
+    fn threshold_signature_shares_combine_to_group_signature() {
+        use fff::Field;
+        use groupy::{CurveAffine, CurveProjective};
+
+        // 2 of 3 committee sharing f(x) = 42 + 7x
+        let share = |x: u64| {
+            let mut y = scalar(7);
+            y.mul_assign(&scalar(x));
+            y.add_assign(&scalar(42));
+            y
+        };
+
+        // Lagrange coefficient of share `i` at zero among `signers`
+        let lagrange = |i: u64, signers: &[u64]| {
+            let mut coefficient = scalar(1);
+            for &j in signers.iter().filter(|&&j| j != i) {
+                let mut denominator = scalar(j);
+                denominator.sub_assign(&scalar(i));
+                coefficient.mul_assign(&scalar(j));
+                coefficient.mul_assign(&denominator.inverse().unwrap());
+            }
+            coefficient
+        };
+
+        let group_public_key = signer::public_key(scalar(42));
+        let group_signature = signer::sign(scalar(42), b"request");
+
+        // any two members produce the same, unique group signature
+        for signers in [[1, 3], [2, 3]] {
+            let mut combined = paired::bls12_381::G2::zero();
+            for &i in signers.iter() {
+                let partial = signer::sign_point(share(i), b"request").into_affine();
+                combined.add_assign(&partial.mul(lagrange(i, &signers)));
+            }
+
+            let combined = hex::encode(combined.into_affine().into_compressed());
+            assert_eq!(combined, group_signature);
+            assert_eq!(verify_bls_signature(&group_public_key, b"request", &combined), Ok(()));
+        }
+    }
+}
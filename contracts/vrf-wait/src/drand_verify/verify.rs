@@ -1,14 +1,15 @@
 use fff::Field;
 use groupy::{CurveAffine, CurveProjective};
-use paired::bls12_381::{Bls12, Fq12, G1Affine, G2Affine, G2};
+use paired::bls12_381::{Bls12, Fq12, G1Affine, G2Affine, G1, G2};
 use paired::{Engine, ExpandMsgXmd, HashToCurve, PairingCurveAffine};
 use sha2::{Digest, Sha256};
 use std::error::Error;
 use std::fmt;
 
 const DOMAIN: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_NUL_";
+const DOMAIN_G1: &[u8] = b"BLS_SIG_BLS12381G1_XMD:SHA-256_SSWU_RO_NUL_";
 
-use super::points::g2_from_variable;
+use super::points::{g1_from_variable, g2_from_variable};
 
 #[derive(Debug)]
 pub enum VerificationError {
@@ -71,6 +72,54 @@ pub fn verify_step2(
     Ok(fast_pairing_equality(&g1, &sigma, pk, msg_on_g2))
 }
 
+/// Verify checks beacon components of an unchained network (signatures on G2).
+pub fn verify_unchained(
+    pk: &G1Affine,
+    round: u64,
+    signature: &[u8],
+) -> Result<bool, VerificationError> {
+    verify(pk, round, b"", signature)
+}
+
+/// Verifies a signature on G2 of an arbitrary message under a public key on G1,
+/// hashing the message to G2 like drand does.
+pub fn verify_message(
+    pk: &G1Affine,
+    msg: &[u8],
+    signature: &[u8],
+) -> Result<bool, VerificationError> {
+    verify_step2(pk, signature, &msg_to_curve(msg))
+}
+
+/// Verify checks beacon components of an unchained network with signatures on G1
+/// and public key on G2.
+///
+/// `rfc_domain` selects the hash to G1 domain of the `bls-unchained-g1-rfc9380` scheme
+/// (quicknet). Without it the G2 domain is used, as required by `bls-unchained-on-g1`
+/// networks (fastnet, testnet-g) for compatibility with a bug in drand.
+pub fn verify_g1_signature(
+    pk: &G2Affine,
+    round: u64,
+    signature: &[u8],
+    rfc_domain: bool,
+) -> Result<bool, VerificationError> {
+    let domain = if rfc_domain { DOMAIN_G1 } else { DOMAIN };
+    let msg_on_g1 = <G1 as HashToCurve<ExpandMsgXmd<sha2::Sha256>>>::hash_to_curve(message(round, b""), domain)
+        .into_affine();
+
+    let g2 = G2Affine::one();
+    let sigma = match g1_from_variable(signature) {
+        Ok(sigma) => sigma,
+        Err(err) => {
+            return Err(VerificationError::InvalidPoint {
+                field: "signature".into(),
+                msg: err.to_string(),
+            })
+        }
+    };
+    Ok(fast_pairing_equality(&sigma, &g2, &msg_on_g1, pk))
+}
+
 /// Checks if e(p, q) == e(r, s)
 ///
 /// See https://hackmd.io/@benjaminion/bls12-381#Final-exponentiation.
@@ -109,7 +158,7 @@ fn round_to_bytes(round: u64) -> [u8; 8] {
     round.to_be_bytes()
 }
 
-fn msg_to_curve(msg: &[u8]) -> G2Affine {
+pub(crate) fn msg_to_curve(msg: &[u8]) -> G2Affine {
     let g = <G2 as HashToCurve<ExpandMsgXmd<sha2::Sha256>>>::hash_to_curve(msg, DOMAIN);
     g.into_affine()
 }
@@ -0,0 +1,252 @@
+//! ECVRF-EDWARDS25519-SHA512-TAI verification as specified in RFC 9381
+//! https://www.rfc-editor.org/rfc/rfc9381#section-5
+
+use curve25519_dalek::constants::ED25519_BASEPOINT_POINT;
+use curve25519_dalek::edwards::{CompressedEdwardsY, EdwardsPoint};
+use curve25519_dalek::scalar::Scalar;
+use sha2::{Digest, Sha512};
+use thiserror::Error;
+
+/// suite_string of ECVRF-EDWARDS25519-SHA512-TAI
+const SUITE: u8 = 0x03;
+
+const ENCODE_TO_CURVE_FRONT: u8 = 0x01;
+const CHALLENGE_FRONT: u8 = 0x02;
+const PROOF_TO_HASH_FRONT: u8 = 0x03;
+const DOMAIN_SEPARATOR_BACK: u8 = 0x00;
+
+/// Length of a compressed edwards25519 point
+const POINT_LENGTH: usize = 32;
+/// Length of the challenge `c` in a proof
+const CHALLENGE_LENGTH: usize = 16;
+/// Length of a proof: Gamma || c || s
+const PROOF_LENGTH: usize = POINT_LENGTH + CHALLENGE_LENGTH + 32;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum VrfError {
+    #[error("Invalid hex in {field}")]
+    InvalidHex { field: String },
+
+    #[error("Invalid length of {field}: expected {expected} bytes, got {actual}")]
+    InvalidLength { field: String, expected: usize, actual: usize },
+
+    #[error("Invalid curve point for {field}: {msg}")]
+    InvalidPoint { field: String, msg: String },
+
+    #[error("VRF proof verification failed")]
+    VerificationFailed {},
+}
+
+fn decode_fixed_hex(field: &str, value: &str, expected: usize) -> Result<Vec<u8>, VrfError> {
+    let bytes = hex::decode(value).map_err(|_| VrfError::InvalidHex{field: field.to_string()})?;
+
+    if bytes.len() != expected {
+        return Err(VrfError::InvalidLength{
+            field: field.to_string(),
+            expected,
+            actual: bytes.len(),
+        });
+    }
+
+    Ok(bytes)
+}
+
+fn decode_point(field: &str, bytes: &[u8]) -> Result<EdwardsPoint, VrfError> {
+    CompressedEdwardsY::from_slice(bytes).decompress().ok_or(VrfError::InvalidPoint{
+        field: field.to_string(),
+        msg: "not a point on edwards25519".to_string(),
+    })
+}
+
+/// Decodes a hex encoded VRF public key, rejecting points of small order
+pub fn decode_public_key(public_key: &str) -> Result<EdwardsPoint, VrfError> {
+    let bytes = decode_fixed_hex("public_key", public_key, POINT_LENGTH)?;
+    let point = decode_point("public_key", &bytes)?;
+
+    if point.is_small_order() {
+        return Err(VrfError::InvalidPoint{
+            field: "public_key".to_string(),
+            msg: "point of small order".to_string(),
+        });
+    }
+
+    Ok(point)
+}
+
+/// Verifies the hex encoded `proof` of `alpha` under the hex encoded public key
+/// and returns the VRF output (beta), 64 bytes.
+pub fn verify(public_key: &str, alpha: &[u8], proof: &str) -> Result<Vec<u8>, VrfError> {
+    let y = decode_public_key(public_key)?;
+    let proof = decode_fixed_hex("proof", proof, PROOF_LENGTH)?;
+
+    let gamma = decode_point("proof", &proof[..POINT_LENGTH])?;
+    let c = challenge_to_scalar(&proof[POINT_LENGTH..POINT_LENGTH + CHALLENGE_LENGTH]);
+    let mut s_bytes = [0u8; 32];
+    s_bytes.copy_from_slice(&proof[POINT_LENGTH + CHALLENGE_LENGTH..]);
+    // s must be reduced modulo the group order
+    let s = Scalar::from_canonical_bytes(s_bytes).ok_or(VrfError::VerificationFailed{})?;
+
+    let h = encode_to_curve(&y, alpha)?;
+    let u = s * ED25519_BASEPOINT_POINT - c * y;
+    let v = s * h - c * gamma;
+
+    if challenge(&[&y, &h, &gamma, &u, &v]) != c {
+        return Err(VrfError::VerificationFailed{});
+    }
+
+    Ok(proof_to_hash(&gamma))
+}
+
+/// ECVRF_encode_to_curve_try_and_increment, the public key is the salt
+fn encode_to_curve(y: &EdwardsPoint, alpha: &[u8]) -> Result<EdwardsPoint, VrfError> {
+    let salt = y.compress();
+
+    for ctr in 0..=u8::MAX {
+        let hash = Sha512::new()
+            .chain([SUITE, ENCODE_TO_CURVE_FRONT])
+            .chain(salt.as_bytes())
+            .chain(alpha)
+            .chain([ctr, DOMAIN_SEPARATOR_BACK])
+            .finalize();
+
+        if let Some(point) = CompressedEdwardsY::from_slice(&hash[..POINT_LENGTH]).decompress() {
+            if !point.is_small_order() {
+                return Ok(point.mul_by_cofactor());
+            }
+        }
+    }
+
+    Err(VrfError::VerificationFailed{})
+}
+
+fn challenge_to_scalar(c: &[u8]) -> Scalar {
+    let mut bytes = [0u8; 32];
+    bytes[..CHALLENGE_LENGTH].copy_from_slice(c);
+    Scalar::from_bytes_mod_order(bytes)
+}
+
+/// ECVRF_challenge_generation
+fn challenge(points: &[&EdwardsPoint; 5]) -> Scalar {
+    let mut hasher = Sha512::new().chain([SUITE, CHALLENGE_FRONT]);
+    for point in points {
+        hasher.update(point.compress().as_bytes());
+    }
+    let hash = hasher.chain([DOMAIN_SEPARATOR_BACK]).finalize();
+
+    challenge_to_scalar(&hash[..CHALLENGE_LENGTH])
+}
+
+/// ECVRF_proof_to_hash
+fn proof_to_hash(gamma: &EdwardsPoint) -> Vec<u8> {
+    Sha512::new()
+        .chain([SUITE, PROOF_TO_HASH_FRONT])
+        .chain(gamma.mul_by_cofactor().compress().as_bytes())
+        .chain([DOMAIN_SEPARATOR_BACK])
+        .finalize()
+        .to_vec()
+}
+
+/// Generator side of the VRF, only needed to produce proofs in tests
+#[cfg(test)]
+pub mod prover {
+    use super::*;
+
+    fn expand_secret_key(secret_key: &[u8; 32]) -> (Scalar, Vec<u8>) {
+        let hash = Sha512::digest(secret_key);
+        let mut x = [0u8; 32];
+        x.copy_from_slice(&hash[..32]);
+        x[0] &= 248;
+        x[31] &= 127;
+        x[31] |= 64;
+        (Scalar::from_bits(x), hash[32..].to_vec())
+    }
+
+    /// Hex encoded public key of `secret_key`
+    pub fn public_key(secret_key: &[u8; 32]) -> String {
+        let (x, _) = expand_secret_key(secret_key);
+        hex::encode((x * ED25519_BASEPOINT_POINT).compress().as_bytes())
+    }
+
+    /// Hex encoded proof of `alpha` under `secret_key`
+    pub fn prove(secret_key: &[u8; 32], alpha: &[u8]) -> String {
+        let (x, prefix) = expand_secret_key(secret_key);
+        let y = x * ED25519_BASEPOINT_POINT;
+        let h = encode_to_curve(&y, alpha).unwrap();
+        let gamma = x * h;
+
+        let nonce = Sha512::new().chain(prefix).chain(h.compress().as_bytes()).finalize();
+        let mut wide = [0u8; 64];
+        wide.copy_from_slice(&nonce);
+        let k = Scalar::from_bytes_mod_order_wide(&wide);
+
+        let c = challenge(&[&y, &h, &gamma, &(k * ED25519_BASEPOINT_POINT), &(k * h)]);
+        let s = k + c * x;
+
+        let mut proof = gamma.compress().as_bytes().to_vec();
+        proof.extend(&c.as_bytes()[..CHALLENGE_LENGTH]);
+        proof.extend(s.as_bytes());
+        hex::encode(proof)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+
+    // Test vectors of ECVRF-EDWARDS25519-SHA512-TAI, RFC 9381 appendix B.3
+    const SK_16: [u8; 32] = hex!("9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60");
+    const PK_16: &str = "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a";
+    const PI_16: &str = "8657106690b5526245a92b003bb079ccd1a92130477671f6fc01ad16f26f723f26f8a57ccaed74ee1b190bed1f479d9727d2d0f9b005a6e456a35d4fb0daab1268a1b0db10836d9826a528ca76567805";
+    const BETA_16: [u8; 64] = hex!("90cf1df3b703cce59e2a35b925d411164068269d7b2d29f3301c03dd757876ff66b71dda49d2de59d03450451af026798e8f81cd2e333de5cdf4f3e140fdd8ae");
+
+    const SK_17: [u8; 32] = hex!("4ccd089b28ff96da9db6c346ec114e0f5b8a319f35aba624da8cf6ed4fb8a6fb");
+    const PI_17: &str = "f3141cd382dc42909d19ec5110469e4feae18300e94f304590abdced48aed5933bf0864a62558b3ed7f2fea45c92a465301b3bbf5e3e54ddf2d935be3b67926da3ef39226bbc355bdc9850112c8f4b02";
+    const BETA_17: [u8; 64] = hex!("eb4440665d3891d668e7e0fcaf587f1b4bd7fbfe99d0eb2211ccec90496310eb5e33821bc613efb94db5e5b54c70a848a0bef4553a41befc57663b56373a5031");
+
+    const SK_18: [u8; 32] = hex!("c5aa8df43f9f837bedb7442f31dcb7b166d38535076f094b85ce3a2e0b4458f7");
+    const PI_18: &str = "9bc0f79119cc5604bf02d23b4caede71393cedfbb191434dd016d30177ccbf8096bb474e53895c362d8628ee9f9ea3c0e52c7a5c691b6c18c9979866568add7a2d41b00b05081ed0f58ee5e31b3a970e";
+    const BETA_18: [u8; 64] = hex!("645427e5d00c62a23fb703732fa5d892940935942101e456ecca7bb217c61c452118fec1219202a0edcf038bb6373241578be7217ba85a2687f7a0310b2df19f");
+
+    #[test]
+    fn verify_works() {
+        assert_eq!(verify(PK_16, b"", PI_16).unwrap(), BETA_16.to_vec());
+        assert_eq!(verify(&prover::public_key(&SK_17), &hex!("72"), PI_17).unwrap(), BETA_17.to_vec());
+        assert_eq!(verify(&prover::public_key(&SK_18), &hex!("af82"), PI_18).unwrap(), BETA_18.to_vec());
+    }
+
+    #[test]
+    fn verify_rejects_wrong_input() {
+        assert_eq!(verify(PK_16, &hex!("72"), PI_16), Err(VrfError::VerificationFailed{}));
+        assert_eq!(verify(&prover::public_key(&SK_17), b"", PI_16), Err(VrfError::VerificationFailed{}));
+
+        // flipped bit in s
+        let corrupted = format!("{}{}", &PI_16[..PI_16.len() - 2], "04");
+        assert_eq!(verify(PK_16, b"", &corrupted), Err(VrfError::VerificationFailed{}));
+
+        // s not reduced modulo the group order
+        let unreduced = format!("{}{}", &PI_16[..96], "ff".repeat(32));
+        assert_eq!(verify(PK_16, b"", &unreduced), Err(VrfError::VerificationFailed{}));
+    }
+
+    #[test]
+    fn verify_rejects_malformed_input() {
+        assert_eq!(verify(PK_16, b"", "zz"), Err(VrfError::InvalidHex{field: "proof".to_string()}));
+        assert_eq!(verify(PK_16, b"", "aabb"),
+            Err(VrfError::InvalidLength{field: "proof".to_string(), expected: 80, actual: 2}));
+        assert_eq!(verify("aabb", b"", PI_16),
+            Err(VrfError::InvalidLength{field: "public_key".to_string(), expected: 32, actual: 2}));
+
+        // the identity is of small order
+        let identity = format!("01{}", "00".repeat(31));
+        assert!(matches!(decode_public_key(&identity), Err(VrfError::InvalidPoint{..})));
+    }
+
+    #[test]
+    fn prove_matches_test_vectors() {
+        assert_eq!(prover::public_key(&SK_16), PK_16);
+        assert_eq!(prover::prove(&SK_16, b""), PI_16);
+        assert_eq!(prover::prove(&SK_17, &hex!("72")), PI_17);
+        assert_eq!(prover::prove(&SK_18, &hex!("af82")), PI_18);
+    }
+}
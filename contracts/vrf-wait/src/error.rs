@@ -1,6 +1,10 @@
 use cosmwasm_std::StdError;
+use generator_staking::StakingError;
 use thiserror::Error;
 
+use crate::drand_verify::DrandError;
+use crate::ecvrf::VrfError;
+
 #[derive(Error, Debug)]
 pub enum ContractError {
     #[error("{0}")]
@@ -11,6 +15,184 @@ pub enum ContractError {
 
     #[error("Custom Error val: {val:?}")]
     CustomError { val: String },
+
+    #[error("Randomness request {id} not found")]
+    RequestNotFound { id: u64 },
+
+    #[error("Randomness request {id} is not pending")]
+    RequestNotPending { id: u64 },
+
+    #[error("Randomness request {id} can no longer be cancelled")]
+    RequestNotCancellable { id: u64 },
+
+    #[error("Randomness request {id} has expired")]
+    RequestExpired { id: u64 },
+
+    #[error("Request expiry must be in the future")]
+    InvalidExpiry {},
+
+    #[error("Number of words must be between 1 and {max}")]
+    InvalidNumWords { max: u32 },
+
+    #[error("Callback gas limit must be between 1 and {max}")]
+    InvalidCallbackGasLimit { max: u64 },
+
+    #[error("Drand round {round} is earlier than round {min_round} required by the request")]
+    DrandRoundTooEarly { round: u64, min_round: u64 },
+
+    #[error("Drand round {round} is later than round {min_round} required by the request")]
+    DrandRoundTooLate { round: u64, min_round: u64 },
+
+    #[error("No pending request can be fulfilled with drand round {round}")]
+    NoRequestToFulfill { round: u64 },
+
+    #[error("Time set {time_set} does not fit in a u64 number of seconds")]
+    InvalidTimeSet { time_set: u128 },
+    #[error("Push replays the drand round or proof already used for request {request_id}")]
+    ReplayedPush { request_id: u64 },
+
+    #[error("Invalid hex in {field}")]
+    InvalidHex { field: String },
+
+    #[error("Invalid length of {field}: expected {expected} bytes, got {actual}")]
+    InvalidLength { field: String, expected: usize, actual: usize },
+
+    #[error("Invalid curve point for {field}: {msg}")]
+    InvalidPoint { field: String, msg: String },
+
+    #[error("Drand pairing check failed")]
+    DrandVerificationFailed {},
+
+    #[error("Previous signature is required by chained drand schemes")]
+    MissingPreviousSignature {},
+
+    #[error("Public key is already registered by another generator")]
+    PublicKeyInUse {},
+    #[error("Request fee of {expected} must be paid exactly")]
+    InvalidRequestFee { expected: String },
+
+    #[error("A stake of at least {min_stake} is required")]
+    InsufficientStake { min_stake: String },
+
+    #[error("Stake is unbonding until {release_at}")]
+    StakeNotUnbonded { release_at: String },
+
+    #[error("Invalid VRF proof")]
+    InvalidVrfProof {},
+
+    #[error("Generator is not registered")]
+    GeneratorNotRegistered {},
+
+    #[error("Generator is already registered")]
+    AlreadyRegistered {},
+
+    #[error("No generator is registered to fulfill the request")]
+    NoGenerator {},
+
+    #[error("Randomness request {id} is assigned to another generator")]
+    GeneratorNotAssigned { id: u64 },
+    #[error("Randomness request {id} is fulfilled in another randomness mode")]
+    WrongRandomnessMode { id: u64 },
+
+    #[error("Randomness request {id} is not in its {phase} phase")]
+    WrongCommitRevealPhase { id: u64, phase: String },
+
+    #[error("Generator already committed to request {id}")]
+    AlreadyCommitted { id: u64 },
+
+    #[error("Randomness request {id} already has the maximum of {max} committers")]
+    TooManyCommitters { id: u64, max: u32 },
+
+    #[error("Maximum committers must not be below the minimum reveals")]
+    InvalidMaxCommitters {},
+
+    #[error("Generator has no commitment to request {id}")]
+    NoCommitment { id: u64 },
+
+    #[error("Secret does not match the commitment to request {id}")]
+    CommitmentMismatch { id: u64 },
+
+    #[error("No committee is registered for threshold requests")]
+    NoCommittee {},
+
+    #[error("Invalid committee group signature")]
+    InvalidGroupSignature {},
+
+    #[error("Threshold must be between one and the {members} committee members")]
+    InvalidCommitteeThreshold { members: usize },
+
+    #[error("Randomness request {id} cannot be fulfilled before {time}")]
+    RequestNotDue { id: u64, time: u64 },
+
+    #[error("Evidence does not show two different outputs for one request")]
+    EvidenceNotConflicting {},
+
+    #[error("Evidence is not made over the drand beacon of round {round}")]
+    EvidenceBeaconMismatch { round: u64 },
+
+    #[error("Evidence for round {round} does not match round {min_round} required by the request")]
+    EvidenceRoundMismatch { round: u64, min_round: u64 },
+
+    #[error("Generators cannot report their own offences")]
+    SelfReport {},
+
+    #[error("Offence of {generator} for request {request_id} is already recorded")]
+    OffenceAlreadyRecorded { generator: String, request_id: u64 },
+
+    #[error("Reporter reward must not exceed one")]
+    InvalidReporterReward {},
+
+    #[error("Drand period must be greater than zero")]
+    InvalidDrandPeriod {},
+
+    #[error("{field} must not be zero")]
+    MustNotBeZero { field: String },
+
+    #[error("Invalid staking config: {msg}")]
+    InvalidStakingConfig { msg: String },
+
+    #[error("Staking denom cannot be changed")]
+    StakingDenomChanged {},
+
+    #[error("Nothing to claim")]
+    NothingToClaim {},
+
     // Add any other custom errors you like here.
     // Look at https://docs.rs/thiserror/1.0.21/thiserror/ for details.
 }
+
+impl From<DrandError> for ContractError {
+    fn from(err: DrandError) -> Self {
+        match err {
+            DrandError::InvalidHex{field} => ContractError::InvalidHex{field},
+            DrandError::InvalidLength{field, expected, actual} => ContractError::InvalidLength{field, expected, actual},
+            DrandError::InvalidPoint{field, msg} => ContractError::InvalidPoint{field, msg},
+            DrandError::VerificationFailed{} => ContractError::DrandVerificationFailed{},
+            DrandError::MissingPreviousSignature{} => ContractError::MissingPreviousSignature{},
+        }
+    }
+}
+
+impl From<VrfError> for ContractError {
+    fn from(err: VrfError) -> Self {
+        match err {
+            VrfError::InvalidHex{field} => ContractError::InvalidHex{field},
+            VrfError::InvalidLength{field, expected, actual} => ContractError::InvalidLength{field, expected, actual},
+            VrfError::InvalidPoint{field, msg} => ContractError::InvalidPoint{field, msg},
+            VrfError::VerificationFailed{} => ContractError::InvalidVrfProof{},
+        }
+    }
+}
+
+impl From<StakingError> for ContractError {
+    fn from(err: StakingError) -> Self {
+        match err {
+            StakingError::Std(err) => ContractError::Std(err),
+            StakingError::InvalidStakingConfig{msg} => ContractError::InvalidStakingConfig{msg},
+            StakingError::StakingDenomChanged{} => ContractError::StakingDenomChanged{},
+            StakingError::InsufficientStake{min_stake} => ContractError::InsufficientStake{min_stake},
+            StakingError::NothingToClaim{} => ContractError::NothingToClaim{},
+            StakingError::StakeNotUnbonded{release_at} => ContractError::StakeNotUnbonded{release_at},
+        }
+    }
+}
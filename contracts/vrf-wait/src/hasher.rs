@@ -8,7 +8,7 @@ pub fn sha256_hash(string: &[u8]) -> Vec<u8> {
     // read hash digest and consume hasher
     let result = hasher.finalize();
 
-    return result.to_vec();
+    result.to_vec()
 }
 
 pub fn sha512_hash(string: &[u8]) -> Vec<u8> {
@@ -18,5 +18,5 @@ pub fn sha512_hash(string: &[u8]) -> Vec<u8> {
     // read hash digest and consume hasher
     let result = hasher.finalize();
 
-    return result.to_vec();
+    result.to_vec()
 }
\ No newline at end of file
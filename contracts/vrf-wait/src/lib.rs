@@ -5,5 +5,6 @@ pub mod state;
 pub mod hasher;
 pub mod call_back;
 pub mod drand_verify;
+pub mod ecvrf;
 
 pub use crate::error::ContractError;
@@ -1,16 +1,82 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Binary, Coin, Decimal};
+
+use crate::state::{Commitment, CommitRevealConfig, Delivery, DrandConfig, Expiry, Generator, RandomnessMode,
+    RandomnessRequest, StakingConfig};
+// only named in `#[returns]`, which is not compiled for wasm32
+#[cfg(not(target_arch = "wasm32"))]
+use crate::state::{Committee, Config, FulfilledResult, Offence, Unbonding};
 
 /// Message type for `instantiate` entry_point
 #[cw_serde]
-pub struct InstantiateMsg {}
+pub struct InstantiateMsg {
+    pub drand: DrandConfig,
+    pub request_fee: Option<Coin>,
+    pub staking: StakingConfig,
+    /// Share of slashed stake paid to reporters of an offence
+    pub reporter_reward: Decimal,
+    pub commit_reveal: CommitRevealConfig,
+    pub request_timeout: Option<u64>,
+    pub max_num_words: u32,
+    pub max_callback_gas_limit: u64,
+}
 
 /// Message type for `execute` entry_point
 #[cw_serde]
 pub enum ExecuteMsg {
-    PushRandomness{randomness:String,signature:String,drand_response:DrandResponse},
-    RequestRandomness{key_hash:String, time_set: u128},
+    /// `proof` is the hex encoded ECVRF-EDWARDS25519-SHA512-TAI proof, under the generator's
+    /// public key, of the preimage binding contract address, chain id, request id, drand round
+    /// and drand randomness
+    PushRandomness{proof:String,drand_response:DrandResponse,request_id:Option<u64>},
+    /// `expires` has to be after the drand round of the request is due, without it the request
+    /// expires the configured request timeout after that.
+    /// `num_words` 32 byte words are delivered, at most the configured maximum.
+    /// `user_seed` is mixed into the output and `callback_data` is passed back unchanged.
+    /// The callback goes to `callback_contract`, or the sender, and may use up to
+    /// `callback_gas_limit`, or the configured maximum. With `Store` delivery no callback is made
+    /// and the randomness is queried through `RandomnessResult`.
+    RequestRandomness{user_seed:Binary, #[serde(default)] callback_data: Binary, time_set: u128, num_words: u32,
+        #[serde(default)] mode: RandomnessMode, #[serde(default)] delivery: Delivery, #[serde(default)] expires: Option<Expiry>,
+        #[serde(default)] callback_contract: Option<String>, #[serde(default)] callback_gas_limit: Option<u64>},
+    /// `public_key` is the hex encoded edwards25519 VRF public key of the generator,
+    /// at least the minimum stake has to be sent along
     Register{public_key:String},
+    /// Deregisters the sender, its stake starts unbonding
     DeleteGenerator{},
+    /// Withdraws the sender's stake once unbonded
+    ClaimUnbonded{},
+    UpdateDrandConfig{drand:DrandConfig},
+    UpdateRequestFee{request_fee:Option<Coin>},
+    UpdateStakingConfig{staking:StakingConfig},
+    UpdateReporterReward{reporter_reward:Decimal},
+    UpdateCommitRevealConfig{commit_reveal:CommitRevealConfig},
+    UpdateRequestTimeout{request_timeout:Option<u64>},
+    UpdateMaxNumWords{max_num_words:u32},
+    UpdateMaxCallbackGasLimit{max_callback_gas_limit:u64},
+    /// Cancels a pending request of the sender and refunds its fee. A VRF or threshold request
+    /// cannot be cancelled once its drand round is due, a commit-reveal request once a generator
+    /// committed or the commit phase is over.
+    CancelRequest{id:u64},
+    /// Marks up to `limit` expired requests as expired and refunds their fees, anyone may sweep
+    SweepExpired{limit:Option<u32>},
+    /// Proves that `generator` produced two different outputs for the same request, both for
+    /// the round of `drand_response` and one of them over its randomness. The generator is
+    /// slashed and deregistered, the sender receives the reporter reward.
+    SubmitEvidence{generator:String,drand_response:DrandResponse,first:SignedPush,second:SignedPush},
+    /// Commits a registered generator to a commit-reveal request, `commitment` is the hex
+    /// encoded sha256 of its secret
+    Commit{request_id:u64,commitment:String},
+    /// Reveals the hex encoded secret of a commitment once the commit phase is over
+    Reveal{request_id:u64,secret:String},
+    /// Mixes all reveals with the drand beacon of the request once the reveal phase is over,
+    /// generators that did not reveal are slashed
+    FinalizeCommitReveal{request_id:u64,drand_response:DrandResponse},
+    /// Sets the committee fulfilling threshold requests, `public_key` is its hex encoded
+    /// BLS group public key on G1
+    RegisterCommittee{public_key:String,members:Vec<String>,threshold:u32},
+    /// Fulfills a threshold request with the hex encoded group signature on G2 of the
+    /// message binding contract address, chain id, request id and min round
+    PushThresholdSignature{request_id:u64,signature:String},
 }
 
 /// Message type for `migrate` entry_point
@@ -28,14 +94,77 @@ pub enum QueryMsg {
     //
     // #[returns(YourQueryResponse)]
     // YourQuery {},
+    #[returns(Config)]
+    Config{},
+    #[returns(Generator)]
+    Generator{addr:String},
+    #[returns(GeneratorsResponse)]
+    ListGenerators{start_after:Option<String>, limit:Option<u32>},
+    #[returns(Unbonding)]
+    Unbonding{addr:String},
+    #[returns(RandomnessRequest)]
+    Request{id:u64},
+    #[returns(RequestsResponse)]
+    PendingRequests{start_after:Option<u64>, limit:Option<u32>},
+    #[returns(RequestsResponse)]
+    RequestsByUser{user:String, start_after:Option<u64>, limit:Option<u32>},
+    #[returns(FulfilledResult)]
+    FulfilledResult{id:u64},
+    #[returns(RandomnessResultResponse)]
+    RandomnessResult{id:u64},
+    #[returns(Offence)]
+    Offence{generator:String, request_id:u64},
+    #[returns(CommitmentsResponse)]
+    Commitments{request_id:u64},
+    #[returns(Committee)]
+    Committee{},
 }
 
 #[cw_serde]
 pub struct DrandResponse {
     pub round: u64,
     pub signature: String,
-    pub previous_signature: String,
+    /// Only set by networks of the chained scheme
+    pub previous_signature: Option<String>,
+}
+
+/// VRF proof a generator made for a request, as it would be pushed
+#[cw_serde]
+pub struct SignedPush {
+    pub request_id: u64,
+    pub round: u64,
+    /// Hex encoded drand randomness the proof was made over
+    pub drand_randomness: String,
+    pub proof: String,
+}
+
+/// Data returned by `RequestRandomness`
+#[cw_serde]
+pub struct RequestRandomnessResponse {
+    pub request_id: u64,
+}
+
+/// Randomness of a fulfilled request as delivered to its consumer
+#[cw_serde]
+pub struct RandomnessResultResponse {
+    pub request_id: u64,
+    /// Hex encoded 32 byte words
+    pub randomness: Vec<String>,
+    pub round: u64,
+    pub proof: String,
+}
+
+#[cw_serde]
+pub struct GeneratorsResponse {
+    pub generators: Vec<Generator>,
+}
+
+#[cw_serde]
+pub struct RequestsResponse {
+    pub requests: Vec<RandomnessRequest>,
+}
+
+#[cw_serde]
+pub struct CommitmentsResponse {
+    pub commitments: Vec<Commitment>,
 }
-// We define a custom struct for each query response
-// #[cw_serde]
-// pub struct YourQueryResponse {}
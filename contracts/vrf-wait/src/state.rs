@@ -2,21 +2,309 @@
 // see: https://crates.io/crates/cw-storage-plus
 
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Coin};
-use cw_storage_plus::{Deque,Map};
+use cosmwasm_std::{Addr, Binary, BlockInfo, Coin, Decimal, Empty, Timestamp, Uint128};
+use cw_storage_plus::{Index, IndexList, IndexedMap, Item, Map, MultiIndex};
+
+pub use generator_staking::{StakingConfig, Unbonding, UNBONDINGS};
+
+/// Signature scheme of a drand network, named after the network's `schemeID`
+#[cw_serde]
+pub enum DrandScheme {
+    /// Chained beacons signed on G2, each signature covers the previous one
+    #[serde(rename = "pedersen-bls-chained")]
+    PedersenBlsChained,
+    /// Unchained beacons signed on G2
+    #[serde(rename = "pedersen-bls-unchained")]
+    PedersenBlsUnchained,
+    /// Unchained beacons signed on G1 and hashed with the G2 domain (fastnet, testnet-g)
+    #[serde(rename = "bls-unchained-on-g1")]
+    BlsUnchainedOnG1,
+    /// Unchained beacons signed on G1 as specified in RFC 9380 (quicknet)
+    #[serde(rename = "bls-unchained-g1-rfc9380")]
+    BlsUnchainedG1Rfc9380,
+}
+
+impl DrandScheme {
+    /// Whether beacons of this scheme carry a previous signature
+    pub fn is_chained(&self) -> bool {
+        matches!(self, DrandScheme::PedersenBlsChained)
+    }
+
+    /// Whether the group public key is on G2 and signatures are on G1
+    pub fn signs_on_g1(&self) -> bool {
+        matches!(self, DrandScheme::BlsUnchainedOnG1 | DrandScheme::BlsUnchainedG1Rfc9380)
+    }
+}
+
+/// Chain info of the drand network beacons are verified against
+#[cw_serde]
+pub struct DrandConfig {
+    /// Hex encoded group public key
+    pub public_key: String,
+    /// Hex encoded chain hash
+    pub chain_hash: String,
+    /// Unix time in seconds of round 1
+    pub genesis_time: u64,
+    /// Seconds between two rounds
+    pub period: u64,
+    pub scheme: DrandScheme,
+}
+
+/// Phases of requests fulfilled by commit-reveal among several generators
+#[cw_serde]
+pub struct CommitRevealConfig {
+    /// Seconds after the request during which generators commit
+    pub commit_period: u64,
+    /// Seconds after the commit phase during which generators reveal
+    pub reveal_period: u64,
+    /// Valid reveals needed to fulfill a request, with fewer it is cancelled
+    pub min_reveals: u32,
+    /// Most generators that may commit to a request, bounding the work of finalizing it
+    pub max_committers: u32,
+}
+
+#[cw_serde]
+pub struct Config {
+    pub owner: Addr,
+    pub drand: DrandConfig,
+    /// Fee paid with every randomness request, `None` if requests are free
+    pub request_fee: Option<Coin>,
+    pub staking: StakingConfig,
+    /// Share of slashed stake paid to whoever submitted evidence of the offence
+    pub reporter_reward: Decimal,
+    pub commit_reveal: CommitRevealConfig,
+    /// Seconds after their drand round is due until requests without their own expiry
+    /// expire, `None` if they never do
+    pub request_timeout: Option<u64>,
+    /// Most random words a single request may ask for
+    pub max_num_words: u32,
+    /// Most gas a request may grant its callback, also used for requests without a limit
+    pub max_callback_gas_limit: u64,
+}
 
 #[cw_serde]
 pub struct Generator {
     pub addr: Addr,
     pub public_key: String,
+    /// Bonded amount of the staking denom
+    pub stake: Uint128,
+}
+
+/// How the randomness of a request is produced
+#[cw_serde]
+#[derive(Default)]
+pub enum RandomnessMode {
+    /// A single generator pushes a VRF proof
+    #[default]
+    Vrf,
+    /// Registered generators commit to secrets and reveal them, all reveals are mixed together
+    CommitReveal,
+    /// The committee signs the request with its threshold group key, the signature is the randomness
+    Threshold,
+}
+
+/// How the randomness of a fulfilled request reaches the requester
+#[cw_serde]
+#[derive(Default)]
+pub enum Delivery {
+    /// The callback contract is executed with the randomness
+    #[default]
+    Callback,
+    /// The randomness is only stored, to be queried by the requester
+    Store,
+}
+
+/// Generator committee holding the shares of a BLS group key from an off-chain DKG
+#[cw_serde]
+pub struct Committee {
+    /// Hex encoded group public key on G1
+    pub public_key: String,
+    /// Members sharing the fees of threshold requests
+    pub members: Vec<Addr>,
+    /// Signature shares needed to produce the group signature
+    pub threshold: u32,
+}
+
+/// Deadlines of a commit-reveal request
+#[cw_serde]
+pub struct CommitRevealWindow {
+    pub commit_deadline: Timestamp,
+    pub reveal_deadline: Timestamp,
+}
+
+/// Commitment of a generator to a commit-reveal request
+#[cw_serde]
+pub struct Commitment {
+    pub generator: Addr,
+    /// Hex encoded sha256 of the secret
+    pub commitment: String,
+    /// Hex encoded secret, once revealed
+    pub secret: Option<String>,
+}
+
+#[cw_serde]
+pub enum RequestStatus {
+    Pending,
+    Fulfilled,
+    Failed,
+    Cancelled,
+    Expired,
+}
+
+impl RequestStatus {
+    /// Key used by the `status` index of `RANDOMNESS_REQUESTS`
+    pub fn key(&self) -> String {
+        match self {
+            RequestStatus::Pending => "pending".to_string(),
+            RequestStatus::Fulfilled => "fulfilled".to_string(),
+            RequestStatus::Failed => "failed".to_string(),
+            RequestStatus::Cancelled => "cancelled".to_string(),
+            RequestStatus::Expired => "expired".to_string(),
+        }
+    }
+}
+
+/// Block height or time from which a pending request can no longer be fulfilled
+#[cw_serde]
+pub enum Expiry {
+    AtHeight(u64),
+    AtTime(Timestamp),
+}
+
+impl Expiry {
+    pub fn is_expired(&self, block: &BlockInfo) -> bool {
+        match self {
+            Expiry::AtHeight(height) => block.height >= *height,
+            Expiry::AtTime(time) => block.time >= *time,
+        }
+    }
 }
 
 #[cw_serde]
 pub struct RandomnessRequest {
+    pub id: u64,
     pub user: Addr,
-    pub key_hash: String,
+    /// Seed of the requester mixed into the output
+    pub user_seed: Binary,
+    /// Opaque data returned unchanged with the randomness
+    pub callback_data: Binary,
+    /// Contract receiving the randomness, the requester unless set otherwise
+    pub callback_contract: Addr,
+    /// Gas the callback may use before it fails on its own
+    pub callback_gas_limit: u64,
     pub time: u128,
+    /// 32 byte words the fulfilled randomness is expanded into
+    pub num_words: u32,
+    /// Earliest drand round allowed to fulfill this request
+    pub min_round: u64,
+    /// The only generator allowed to fulfill a VRF request, fixed when it is made,
+    /// `None` for commit-reveal requests that any registered generator may join
+    /// and for threshold requests signed by the committee
+    pub assigned_generator: Option<Addr>,
+    pub status: RequestStatus,
+    /// Error returned by the consumer when the randomness callback failed
+    pub callback_error: Option<String>,
+    /// Fee held until the request is fulfilled, cancelled or expires
+    pub escrow: Vec<Coin>,
+    /// How the randomness of the request is produced
+    pub mode: RandomnessMode,
+    pub delivery: Delivery,
+    pub expires: Option<Expiry>,
+    /// Set for requests fulfilled by commit-reveal
+    pub commit_reveal: Option<CommitRevealWindow>,
+}
+
+#[cw_serde]
+pub struct FulfilledResult {
+    pub request_id: u64,
+    /// Hex encoded seed the words are expanded from
+    pub randomness: String,
+    /// Hex encoded words delivered to the consumer
+    pub words: Vec<String>,
+    /// Drand round mixed into the randomness, for threshold requests the round they were due at
+    pub round: u64,
+    /// Generator that pushed the VRF proof, `None` for commit-reveal and threshold requests
+    pub generator: Option<Addr>,
+    /// Hex encoded VRF proof or group signature the randomness was derived from,
+    /// empty for commit-reveal requests whose secrets are kept with their commitments
+    pub proof: String,
 }
 
+pub struct RequestIndexes<'a> {
+    pub status: MultiIndex<'a, String, RandomnessRequest, u64>,
+    pub user: MultiIndex<'a, Addr, RandomnessRequest, u64>,
+    /// (status, assigned generator or empty, min_round), lets a queued push find the oldest request of its round
+    pub round: MultiIndex<'a, (String, String, u64), RandomnessRequest, u64>,
+}
+
+impl<'a> IndexList<RandomnessRequest> for RequestIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<RandomnessRequest>> + '_> {
+        let v: Vec<&dyn Index<RandomnessRequest>> = vec![&self.status, &self.user, &self.round];
+        Box::new(v.into_iter())
+    }
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
+pub const COMMITTEE: Item<Committee> = Item::new("committee");
 pub const GENERATORS: Map<Addr,Generator> = Map::new("generators");
-pub const RANDOMNESS_REQUEST_STATE: Deque<RandomnessRequest> = Deque::new("randomness_request_state");
+/// Generator registered with each VRF public key, keyed by the compressed point in lowercase hex
+pub const PUBLIC_KEYS: Map<&str, Addr> = Map::new("public_keys");
+
+/// Generator the last request was assigned to, requests go round-robin over `GENERATORS`
+pub const LAST_ASSIGNED_GENERATOR: Item<Addr> = Item::new("last_assigned_generator");
+
+/// Id of the last randomness request, ids are handed out in increasing order
+pub const REQUEST_COUNT: Item<u64> = Item::new("request_count");
+
+pub const RANDOMNESS_REQUESTS: IndexedMap<u64, RandomnessRequest, RequestIndexes> = IndexedMap::new(
+    "randomness_requests",
+    RequestIndexes {
+        status: MultiIndex::new(
+            |_pk, request| request.status.key(),
+            "randomness_requests",
+            "randomness_requests__status",
+        ),
+        user: MultiIndex::new(
+            |_pk, request| request.user.clone(),
+            "randomness_requests",
+            "randomness_requests__user",
+        ),
+        round: MultiIndex::new(
+            |_pk, request| (
+                request.status.key(),
+                request.assigned_generator.as_ref().map(Addr::to_string).unwrap_or_default(),
+                request.min_round,
+            ),
+            "randomness_requests",
+            "randomness_requests__round",
+        ),
+    },
+);
+
+pub const FULFILLED_RESULTS: Map<u64, FulfilledResult> = Map::new("fulfilled_results");
+
+/// Requests to sweep keyed by (expiry height, request id), entries are dropped once swept
+pub const EXPIRIES_BY_HEIGHT: Map<(u64, u64), Empty> = Map::new("expiries_by_height");
+/// Requests to sweep keyed by (expiry time in nanoseconds, request id)
+pub const EXPIRIES_BY_TIME: Map<(u64, u64), Empty> = Map::new("expiries_by_time");
+
+/// Commitments to commit-reveal requests keyed by (request id, generator)
+pub const COMMITMENTS: Map<(u64, Addr), Commitment> = Map::new("commitments");
+
+/// Equivocation of a generator, proven by two different VRF outputs for one request
+#[cw_serde]
+pub struct Offence {
+    pub generator: Addr,
+    pub request_id: u64,
+    pub reporter: Addr,
+    pub slashed: Uint128,
+    pub height: u64,
+}
+
+/// Recorded offences keyed by (generator, request id), each is punished once
+pub const OFFENCES: Map<(Addr, u64), Offence> = Map::new("offences");
+
+/// Generator that consumed a drand round for a request, keyed by (round, request id)
+pub const CONSUMED_ROUNDS: Map<(u64, u64), Addr> = Map::new("consumed_rounds");
+/// Request fulfilled by each pushed VRF proof, keyed by the raw proof bytes
+pub const USED_PROOFS: Map<&[u8], u64> = Map::new("used_proofs");
@@ -0,0 +1,24 @@
+use cosmwasm_std::StdError;
+use thiserror::Error;
+
+/// Errors of the staking rules, each contract maps them onto its own `ContractError`
+#[derive(Error, Debug)]
+pub enum StakingError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Invalid staking config: {msg}")]
+    InvalidStakingConfig { msg: String },
+
+    #[error("Staking denom cannot be changed")]
+    StakingDenomChanged {},
+
+    #[error("A stake of at least {min_stake} is required")]
+    InsufficientStake { min_stake: String },
+
+    #[error("Nothing to claim")]
+    NothingToClaim {},
+
+    #[error("Stake is unbonding until {release_at}")]
+    StakeNotUnbonded { release_at: String },
+}
@@ -0,0 +1,8 @@
+pub mod error;
+pub mod staking;
+pub mod state;
+
+pub use crate::error::StakingError;
+pub use crate::staking::{bonded_stake, claim_unbonded, slash, start_unbonding, treasury_payout,
+    validate_staking_config, validate_staking_update, Slash};
+pub use crate::state::{StakingConfig, Unbonding, UNBONDINGS};
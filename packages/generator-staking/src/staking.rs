@@ -0,0 +1,97 @@
+use cosmwasm_std::{Addr, Api, BankMsg, Coin, Decimal, Env, StdResult, Storage, Uint128, coins};
+
+use crate::error::StakingError;
+use crate::state::{StakingConfig, Unbonding, UNBONDINGS};
+
+pub fn validate_staking_config(api: &dyn Api, staking: &StakingConfig) -> Result<(), StakingError> {
+    api.addr_validate(staking.treasury.as_str())?;
+
+    if staking.denom.is_empty() || staking.min_stake.is_zero() {
+        return Err(StakingError::InvalidStakingConfig{msg: "minimum stake must not be zero".to_string()});
+    }
+
+    if staking.slash_fraction > Decimal::one() {
+        return Err(StakingError::InvalidStakingConfig{msg: "slash fraction must not exceed one".to_string()});
+    }
+
+    Ok(())
+}
+
+/// Checks a config replacing `current`
+pub fn validate_staking_update(api: &dyn Api, current: &StakingConfig, staking: &StakingConfig) -> Result<(), StakingError> {
+    validate_staking_config(api, staking)?;
+
+    // changing the denom would mix up bonded coins
+    if staking.denom != current.denom {
+        return Err(StakingError::StakingDenomChanged{});
+    }
+
+    Ok(())
+}
+
+/// Stake bonded by a registration paying `funds`, at least the minimum stake in the staking denom
+pub fn bonded_stake(staking: &StakingConfig, funds: &[Coin]) -> Result<Uint128, StakingError> {
+    match funds {
+        [fund] if fund.denom == staking.denom && fund.amount >= staking.min_stake => Ok(fund.amount),
+        _ => Err(StakingError::InsufficientStake{min_stake: format!("{}{}", staking.min_stake, staking.denom)}),
+    }
+}
+
+/// Adds `amount` to the unbonding stake of `addr`, which restarts its unbonding period
+pub fn start_unbonding(storage: &mut dyn Storage, env: &Env, staking: &StakingConfig, addr: &Addr, amount: Uint128) -> StdResult<()> {
+    if amount.is_zero() {
+        return Ok(());
+    }
+
+    UNBONDINGS.update(storage, addr.clone(), |unbonding| -> StdResult<Unbonding> {
+        Ok(Unbonding {
+            amount: unbonding.map(|unbonding| unbonding.amount).unwrap_or_default() + amount,
+            release_at: env.block.time.plus_seconds(staking.unbonding_period),
+        })
+    })?;
+
+    Ok(())
+}
+
+/// Removes the unbonded stake of `addr` once its unbonding period is over and returns it
+pub fn claim_unbonded(storage: &mut dyn Storage, env: &Env, staking: &StakingConfig, addr: &Addr) -> Result<Coin, StakingError> {
+    let unbonding = UNBONDINGS.may_load(storage, addr.clone())?
+        .ok_or(StakingError::NothingToClaim{})?;
+
+    if env.block.time < unbonding.release_at {
+        return Err(StakingError::StakeNotUnbonded{release_at: unbonding.release_at.to_string()});
+    }
+
+    UNBONDINGS.remove(storage, addr.clone());
+
+    Ok(Coin::new(unbonding.amount.u128(), &staking.denom))
+}
+
+/// Stake taken from a generator and what is left of it
+pub struct Slash {
+    pub slashed: Uint128,
+    /// `None` when the rest fell below the minimum stake and started unbonding,
+    /// the generator has to be deregistered then
+    pub remaining: Option<Uint128>,
+}
+
+/// Takes the configured share of the `stake` of `addr`
+pub fn slash(storage: &mut dyn Storage, env: &Env, staking: &StakingConfig, addr: &Addr, stake: Uint128) -> StdResult<Slash> {
+    let slashed = stake.mul_floor(staking.slash_fraction);
+    let rest = stake - slashed;
+
+    if rest < staking.min_stake {
+        start_unbonding(storage, env, staking, addr, rest)?;
+        return Ok(Slash{slashed, remaining: None});
+    }
+
+    Ok(Slash{slashed, remaining: Some(rest)})
+}
+
+/// Message paying `amount` of slashed stake to the treasury, if there is any
+pub fn treasury_payout(staking: &StakingConfig, amount: Uint128) -> Option<BankMsg> {
+    (!amount.is_zero()).then(|| BankMsg::Send {
+        to_address: staking.treasury.to_string(),
+        amount: coins(amount.u128(), &staking.denom),
+    })
+}
@@ -0,0 +1,26 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Decimal, Timestamp, Uint128};
+use cw_storage_plus::Map;
+
+/// Bond generators have to keep while registered
+#[cw_serde]
+pub struct StakingConfig {
+    pub denom: String,
+    /// Stake required to register
+    pub min_stake: Uint128,
+    /// Seconds a stake stays slashable after its generator left
+    pub unbonding_period: u64,
+    /// Share of the stake taken for each offence
+    pub slash_fraction: Decimal,
+    /// Receiver of slashed stake
+    pub treasury: Addr,
+}
+
+/// Stake of a deregistered or slashed generator waiting to be claimed
+#[cw_serde]
+pub struct Unbonding {
+    pub amount: Uint128,
+    pub release_at: Timestamp,
+}
+
+pub const UNBONDINGS: Map<Addr, Unbonding> = Map::new("unbondings");